@@ -0,0 +1,478 @@
+// MIT License
+// 
+// Copyright (c) 2019 Jonathon Davis
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software. 
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+/*! 
+ Provides a builder and parser for the Set-Cookie header. 
+
+ Cookies sent with a request can be read with request::cookie_iter and filtered on with
+ request::Filter::filter_cookie, and the cookies set by a response can be read with
+ response::set_cookie_iter and filtered on with response::Filter::filter_set_cookie. With the
+ secure-cookies feature, a CookieJar can be used to sign and encrypt cookies.
+*/
+/* ============================================================================================ */
+/*     Document Structure                                                                       */
+/*          Same Site                                                                           */
+/*          Set Cookie                                                                          */
+/*          Encoding                                                                            */
+/*          Test Cases                                                                          */
+/* ============================================================================================ */
+use std::fmt;
+use std::time::{Duration, UNIX_EPOCH};
+use http::header::{HeaderValue, SET_COOKIE};
+use http::response::Builder;
+use crate::header::{is_token, HttpDate};
+
+#[cfg(feature = "secure-cookies")]
+mod jar;
+#[cfg(feature = "secure-cookies")]
+pub use self::jar::{CookieJar, Key};
+
+/* ============================================================================================ */
+/*     Same Site                                                                                */
+/* ============================================================================================ */
+
+/// The value of the SameSite attribute, which controls whether a cookie is sent with requests
+/// that come from other sites
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SameSite {
+    /// The cookie is only sent with requests from the same site
+    Strict,
+    /// The cookie is also sent when navigating to the site from another site
+    Lax,
+    /// The cookie is sent with every request, this requires the cookie to be Secure
+    None,
+}
+
+impl SameSite {
+    /// Parses the value of a SameSite attribute without regard to case
+    pub fn parse(value : &str) -> Option<Self> {
+        match value {
+            value if value.eq_ignore_ascii_case("strict") => Some(SameSite::Strict),
+            value if value.eq_ignore_ascii_case("lax") => Some(SameSite::Lax),
+            value if value.eq_ignore_ascii_case("none") => Some(SameSite::None),
+            _ => None,
+        }
+    }
+    /// Returns the value as it is written in the header
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SameSite::Strict => "Strict",
+            SameSite::Lax => "Lax",
+            SameSite::None => "None",
+        }
+    }
+}
+
+impl fmt::Display for SameSite {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/* ============================================================================================ */
+/*     Set Cookie                                                                               */
+/* ============================================================================================ */
+
+/// A cookie along with the attributes of a Set-Cookie header. A SetCookie can be built and 
+/// appended to a response, or parsed from the Set-Cookie header of a response. The name, value
+/// and attributes are borrowed, so building or parsing a SetCookie doesn't allocate.
+/// 
+/// The name of a cookie has to be a token, so it can't be empty or contain characters such as
+/// spaces, '=' or ';'. When the cookie is written, any characters of the value that aren't allowed
+/// in a cookie, such as spaces, quotes, commas and semicolons, are percent encoded along with the
+/// '%' character, this can be reversed with encoding::percent_decode. Semicolons, control 
+/// characters and non ascii characters in the Path and Domain are percent encoded as well, so they
+/// can't add attributes to the header, but a '%' is left as it is so an encoded path is written
+/// unchanged. As browsers reject a cookie with SameSite=None or Partitioned that isn't Secure,
+/// setting either of them also sets the Secure attribute.
+/// # Example
+/// ```
+/// use std::time::Duration;
+/// use http::response::Builder;
+/// use http_tools::cookie::{SameSite, SetCookie};
+/// 
+/// let mut builder = Builder::new();
+/// SetCookie::new("session", "abc123")
+///     .with_path("/")
+///     .with_max_age(Duration::from_secs(3600))
+///     .with_http_only(true)
+///     .with_same_site(SameSite::Lax)
+///     .append_to(&mut builder);
+/// SetCookie::new("greeting", "hello world").append_to(&mut builder);
+/// let response = builder.body(()).unwrap();
+/// 
+/// let mut headers = response.headers().get_all("set-cookie").iter();
+/// assert_eq!(headers.next().unwrap(), "session=abc123; Path=/; Max-Age=3600; HttpOnly; SameSite=Lax");
+/// assert_eq!(headers.next().unwrap(), "greeting=hello%20world");
+/// 
+/// // the headers can be parsed back into a SetCookie
+/// let cookie = SetCookie::parse("session=abc123; Path=/; Max-Age=3600; HttpOnly").unwrap();
+/// assert_eq!(cookie.name(), "session");
+/// assert_eq!(cookie.path(), Some("/"));
+/// assert!(cookie.http_only());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SetCookie<'a> {
+    name : &'a str,
+    value : &'a str,
+    path : Option<&'a str>,
+    domain : Option<&'a str>,
+    max_age : Option<u64>,
+    expires : Option<HttpDate>,
+    secure : bool,
+    http_only : bool,
+    same_site : Option<SameSite>,
+    partitioned : bool,
+}
+
+impl<'a> SetCookie<'a> {
+    /// Creates a cookie with the name and value, and without any attributes
+    /// # Panics
+    /// Panics if the name is empty or isn't a token
+    pub fn new(name : &'a str, value : &'a str) -> Self {
+        if !is_token(name) {
+            panic!("invalid cookie name {:?}", name);
+        }
+        SetCookie {
+            name, 
+            value, 
+            path : None, 
+            domain : None, 
+            max_age : None, 
+            expires : None, 
+            secure : false, 
+            http_only : false, 
+            same_site : None, 
+            partitioned : false,
+        }
+    }
+    /// Creates a cookie that removes the cookie with the name from the client, by giving it an
+    /// empty value that has already expired. The Path and Domain need to be the same as when the
+    /// cookie was set.
+    /// # Panics
+    /// Panics if the name is empty or isn't a token
+    /// # Example
+    /// ```
+    /// use http_tools::cookie::SetCookie;
+    /// 
+    /// let cookie = SetCookie::removal("session").with_path("/");
+    /// assert_eq!(cookie.to_string(), "session=; Path=/; Max-Age=0; Expires=Thu, 01 Jan 1970 00:00:00 GMT");
+    /// ```
+    pub fn removal(name : &'a str) -> Self {
+        SetCookie::new(name, "")
+            .with_max_age(Duration::from_secs(0))
+            .with_expires(HttpDate::from(UNIX_EPOCH))
+    }
+    /// Parses the value of a Set-Cookie header. Attributes are matched without regard to case,
+    /// and unknown attributes or attributes with invalid values are ignored. None is returned if
+    /// the header doesn't begin with a name and value, or if the name isn't a token. The name and
+    /// value are borrowed as they were sent, so a percent encoded value is left encoded, but a 
+    /// quoted value has its quotes removed. A negative Max-Age is returned as a Max-Age of 0.
+    pub fn parse(value : &'a str) -> Option<Self> {
+        let mut parts = value.split(';');
+        let pair = parts.next()?;
+        let equals = pair.find('=')?;
+        let name = pair[..equals].trim();
+        if !is_token(name) {
+            return None;
+        }
+        let value = pair[equals + 1..].trim();
+        let value = match value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+            true => &value[1..value.len() - 1],
+            false => value,
+        };
+        let mut cookie = SetCookie::new(name, value);
+        for attribute in parts {
+            let (key, value) = match attribute.find('=') {
+                Some(equals) => (attribute[..equals].trim(), attribute[equals + 1..].trim()),
+                None => (attribute.trim(), ""),
+            };
+            let is = |name : &str| key.eq_ignore_ascii_case(name);
+            match () {
+                _ if is("path") && value.starts_with('/') => cookie.path = Some(value),
+                // a leading dot is ignored by clients, so it isn't part of the domain
+                _ if is("domain") && !value.is_empty() => cookie.domain = Some(value.trim_start_matches('.')),
+                _ if is("max-age") => {
+                    let digits = value.strip_prefix('-').unwrap_or(value);
+                    if !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()) {
+                        let seconds = digits.parse::<u64>().unwrap_or(u64::MAX);
+                        cookie.max_age = Some(if value.starts_with('-') { 0 } else { seconds });
+                    }
+                }
+                _ if is("expires") => cookie.expires = HttpDate::parse(value).or(cookie.expires),
+                _ if is("secure") => cookie.secure = true,
+                _ if is("httponly") => cookie.http_only = true,
+                _ if is("samesite") => cookie.same_site = SameSite::parse(value).or(cookie.same_site),
+                _ if is("partitioned") => cookie.partitioned = true,
+                _ => (),
+            }
+        }
+        Some(cookie)
+    }
+    /// Returns the name of the cookie
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+    /// Returns the value of the cookie
+    pub fn value(&self) -> &'a str {
+        self.value
+    }
+    /// Returns the Path attribute
+    pub fn path(&self) -> Option<&'a str> {
+        self.path
+    }
+    /// Returns the Domain attribute
+    pub fn domain(&self) -> Option<&'a str> {
+        self.domain
+    }
+    /// Returns the Max-Age attribute
+    pub fn max_age(&self) -> Option<Duration> {
+        self.max_age.map(Duration::from_secs)
+    }
+    /// Returns the Expires attribute
+    pub fn expires(&self) -> Option<HttpDate> {
+        self.expires
+    }
+    /// Returns true if the Secure attribute is present
+    pub fn secure(&self) -> bool {
+        self.secure
+    }
+    /// Returns true if the HttpOnly attribute is present
+    pub fn http_only(&self) -> bool {
+        self.http_only
+    }
+    /// Returns the SameSite attribute
+    pub fn same_site(&self) -> Option<SameSite> {
+        self.same_site
+    }
+    /// Returns true if the Partitioned attribute is present
+    pub fn partitioned(&self) -> bool {
+        self.partitioned
+    }
+    /// Sets the value of the cookie
+    pub fn with_value(mut self, value : &'a str) -> Self {
+        self.value = value;
+        self
+    }
+    /// Sets the Path attribute, which limits the cookie to paths that begin with it
+    pub fn with_path(mut self, path : &'a str) -> Self {
+        self.path = Some(path);
+        self
+    }
+    /// Sets the Domain attribute, which allows the cookie to be sent to subdomains of the domain
+    pub fn with_domain(mut self, domain : &'a str) -> Self {
+        self.domain = Some(domain);
+        self
+    }
+    /// Sets the Max-Age attribute rounded down to the second, a Max-Age of 0 removes the cookie
+    pub fn with_max_age(mut self, max_age : Duration) -> Self {
+        self.max_age = Some(max_age.as_secs());
+        self
+    }
+    /// Sets the Expires attribute, Max-Age takes priority over Expires when both are set
+    pub fn with_expires<T : Into<HttpDate>>(mut self, expires : T) -> Self {
+        self.expires = Some(expires.into());
+        self
+    }
+    /// Sets the Secure attribute, which only allows the cookie to be sent over https
+    pub fn with_secure(mut self, secure : bool) -> Self {
+        self.secure = secure;
+        self
+    }
+    /// Sets the HttpOnly attribute, which hides the cookie from scripts
+    pub fn with_http_only(mut self, http_only : bool) -> Self {
+        self.http_only = http_only;
+        self
+    }
+    /// Sets the SameSite attribute, SameSite::None also sets the Secure attribute as browsers 
+    /// require it
+    pub fn with_same_site(mut self, same_site : SameSite) -> Self {
+        self.same_site = Some(same_site);
+        self.secure |= same_site == SameSite::None;
+        self
+    }
+    /// Sets the Partitioned attribute, which keys the cookie to the top level site. Setting it
+    /// also sets the Secure attribute as browsers require it
+    pub fn with_partitioned(mut self, partitioned : bool) -> Self {
+        self.partitioned = partitioned;
+        self.secure |= partitioned;
+        self
+    }
+    /// Returns the cookie as the value of a Set-Cookie header
+    pub fn to_header_value(&self) -> HeaderValue {
+        // every character that isn't allowed in a header is percent encoded when written
+        HeaderValue::from_str(&self.to_string()).expect("a written cookie is always a valid header value")
+    }
+    /// Appends the cookie to the response as a Set-Cookie header, any Set-Cookie headers that
+    /// were already added are kept
+    pub fn append_to<'b>(&self, builder : &'b mut Builder) -> &'b mut Builder {
+        builder.header(SET_COOKIE, self.to_header_value())
+    }
+}
+
+impl<'a> fmt::Display for SetCookie<'a> {
+    // writes the cookie as the value of a Set-Cookie header
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        // the name is always a token, so it doesn't need to be encoded
+        write!(f, "{}=", self.name)?;
+        write_encoded(f, self.value, is_cookie_octet)?;
+        if let Some(path) = self.path {
+            write!(f, "; Path=")?;
+            write_encoded(f, path, is_attribute_byte)?;
+        }
+        if let Some(domain) = self.domain {
+            write!(f, "; Domain=")?;
+            write_encoded(f, domain, is_attribute_byte)?;
+        }
+        if let Some(max_age) = self.max_age {
+            write!(f, "; Max-Age={}", max_age)?;
+        }
+        if let Some(expires) = self.expires {
+            write!(f, "; Expires={}", expires)?;
+        }
+        if self.secure {
+            write!(f, "; Secure")?;
+        }
+        if self.http_only {
+            write!(f, "; HttpOnly")?;
+        }
+        if let Some(same_site) = self.same_site {
+            write!(f, "; SameSite={}", same_site)?;
+        }
+        if self.partitioned {
+            write!(f, "; Partitioned")?;
+        }
+        Ok(())
+    }
+}
+
+/* ============================================================================================ */
+/*     Encoding                                                                                 */
+/* ============================================================================================ */
+
+// Writes the value, percent encoding any byte that isn't allowed
+fn write_encoded(f : &mut fmt::Formatter, value : &str, allowed : fn(u8) -> bool) -> fmt::Result {
+    // the allowed bytes are all ascii, so the runs of allowed bytes are always valid slices
+    let mut start = 0;
+    for (index, byte) in value.bytes().enumerate() {
+        if !allowed(byte) {
+            if start < index {
+                write!(f, "{}", &value[start..index])?;
+            }
+            write!(f, "%{:02X}", byte)?;
+            start = index + 1;
+        }
+    }
+    if start < value.len() {
+        write!(f, "{}", &value[start..])?;
+    }
+    Ok(())
+}
+
+// The characters allowed in a cookie value by RFC 6265, except for '%' which is encoded so that
+// the value can be decoded again
+fn is_cookie_octet(byte : u8) -> bool {
+    matches!(byte, 0x21 | 0x23..=0x24 | 0x26..=0x2B | 0x2D..=0x3A | 0x3C..=0x5B | 0x5D..=0x7E)
+}
+
+// The characters allowed in the value of a Path or Domain attribute
+fn is_attribute_byte(byte : u8) -> bool {
+    (0x20..0x7F).contains(&byte) && byte != b';'
+}
+
+/* ============================================================================================ */
+/*     Test Cases                                                                               */
+/* ============================================================================================ */
+#[test]
+fn test_set_cookie_write() {
+    let cookie = SetCookie::new("id", "a3fWa")
+        .with_domain("example.com")
+        .with_path("/docs")
+        .with_expires(HttpDate::parse("Wed, 21 Oct 2015 07:28:00 GMT").unwrap())
+        .with_secure(true)
+        .with_http_only(true)
+        .with_same_site(SameSite::Strict);
+    assert_eq!(cookie.to_string(), "id=a3fWa; Path=/docs; Domain=example.com; Expires=Wed, 21 Oct 2015 07:28:00 GMT; Secure; HttpOnly; SameSite=Strict");
+    let cookie = SetCookie::new("id", "1").with_same_site(SameSite::None).with_partitioned(true);
+    assert!(cookie.secure());
+    assert_eq!(cookie.to_string(), "id=1; Secure; SameSite=None; Partitioned");
+    let cookie = SetCookie::new("id", "1").with_partitioned(true).with_secure(false);
+    assert!(!cookie.secure());
+    assert_eq!(cookie.to_string(), "id=1; Partitioned");
+    assert!(!SetCookie::new("id", "1").with_same_site(SameSite::Lax).secure());
+}
+
+#[test]
+fn test_set_cookie_encoding() {
+    let cookie = SetCookie::new("a%b", "x;y,\"z\"\\100%\u{e9}").with_path("/a;Secure\n");
+    assert_eq!(cookie.to_string(), "a%b=x%3By%2C%22z%22%5C100%25%C3%A9; Path=/a%3BSecure%0A");
+    assert_eq!(crate::encoding::percent_decode(SetCookie::parse(&cookie.to_string()).unwrap().value()), "x;y,\"z\"\\100%\u{e9}");
+    assert!(HeaderValue::from_str(&cookie.to_string()).is_ok());
+    let cookie = SetCookie::new("id", "1").with_path("/files/my%20docs/\u{e9}").with_domain("example.com;");
+    assert_eq!(cookie.to_string(), "id=1; Path=/files/my%20docs/%C3%A9; Domain=example.com%3B");
+}
+
+#[test]
+fn test_set_cookie_parse() {
+    let cookie = SetCookie::parse("id=\"a3fWa\"; Max-Age=-1; expires=Wed, 21 Oct 2015 07:28:00 GMT; secure; HTTPONLY; SameSite=lax; Domain=.example.com; Path=/; Partitioned; Unknown=1").unwrap();
+    assert_eq!(cookie.name(), "id");
+    assert_eq!(cookie.value(), "a3fWa");
+    assert_eq!(cookie.max_age(), Some(Duration::from_secs(0)));
+    assert_eq!(cookie.expires().unwrap().year(), 2015);
+    assert!(cookie.secure() && cookie.http_only() && cookie.partitioned());
+    assert_eq!(cookie.same_site(), Some(SameSite::Lax));
+    assert_eq!(cookie.domain(), Some("example.com"));
+    assert_eq!(cookie.path(), Some("/"));
+    let cookie = SetCookie::parse("id=; Max-Age=abc; Expires=never; SameSite=sometimes; Path=relative").unwrap();
+    assert_eq!(cookie, SetCookie::new("id", ""));
+    assert!(SetCookie::parse("novalue").is_none());
+    assert!(SetCookie::parse("=value").is_none());
+}
+
+#[test]
+fn test_set_cookie_append() {
+    let mut builder = Builder::new();
+    builder.header(SET_COOKIE, "first=1");
+    SetCookie::new("second", "2").append_to(&mut builder);
+    SetCookie::removal("third").append_to(&mut builder);
+    let response = builder.body(()).unwrap();
+    let cookies = response.headers().get_all(SET_COOKIE).iter().collect::<Vec<_>>();
+    assert_eq!(cookies, vec!["first=1", "second=2", "third=; Max-Age=0; Expires=Thu, 01 Jan 1970 00:00:00 GMT"]);
+}
+
+#[test]
+fn test_set_cookie_name() {
+    assert!(SetCookie::parse("=value").is_none());
+    assert!(SetCookie::parse("a b=value").is_none());
+    assert!(SetCookie::parse("a;b=value").is_none());
+}
+
+#[test]
+#[should_panic]
+fn test_set_cookie_empty_name() {
+    let _ = SetCookie::new("", "value");
+}
+
+#[test]
+#[should_panic]
+fn test_set_cookie_invalid_name() {
+    let _ = SetCookie::removal("a b");
+}
@@ -0,0 +1,324 @@
+// MIT License
+// 
+// Copyright (c) 2019 Jonathon Davis
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software. 
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+/* ============================================================================================ */
+/*     Document Structure                                                                       */
+/*          Key                                                                                 */
+/*          Cookie Jar                                                                          */
+/*          Test Cases                                                                          */
+/* ============================================================================================ */
+use std::borrow::Cow;
+use std::fmt;
+use aes_gcm::{Aes256Gcm, KeyInit};
+use aes_gcm::aead::{Aead, AeadCore, OsRng, Payload};
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use http::request::Request;
+use http::response::Builder;
+use crate::cookie::SetCookie;
+use crate::encoding::percent_decode;
+use crate::request::cookie_iter;
+
+type HmacSha256 = Hmac<Sha256>;
+
+// The length of a base64 encoded HMAC-SHA256 signature, which prefixes a signed value
+const SIGNATURE_LEN : usize = 43;
+// The length of the nonce that prefixes an encrypted value
+const NONCE_LEN : usize = 12;
+// The length of the authentication tag that follows the encrypted value
+const TAG_LEN : usize = 16;
+
+/* ============================================================================================ */
+/*     Key                                                                                      */
+/* ============================================================================================ */
+
+/// The secret used by a CookieJar to sign and encrypt cookies. Separate keys for signing and
+/// encryption are derived from the secret, so the same secret can be used for both.
+#[derive(Clone)]
+pub struct Key {
+    signing : [u8; 32],
+    encryption : [u8; 32],
+}
+
+impl Key {
+    /// Derives a key from a server secret, the secret should be at least 32 random bytes and
+    /// kept the same across restarts and servers, otherwise cookies won't verify.
+    /// # Panics
+    /// If the secret is shorter than 32 bytes
+    pub fn derive(secret : &[u8]) -> Self {
+        assert!(secret.len() >= 32, "a cookie secret must be at least 32 bytes long");
+        // each key is the hmac of a label using the secret
+        let derive = |label : &[u8]| {
+            let mut mac = <HmacSha256 as Mac>::new_from_slice(secret).expect("hmac accepts keys of any length");
+            mac.update(label);
+            let mut key = [0; 32];
+            key.copy_from_slice(&mac.finalize().into_bytes());
+            key
+        };
+        Key { signing : derive(b"http_tools cookie signing"), encryption : derive(b"http_tools cookie encryption") }
+    }
+    // Creates the hmac of the name and value, the length of the name is included so that the
+    // name and value can't be shifted into each other
+    fn mac(&self, name : &str, value : &str) -> HmacSha256 {
+        let mut mac = <HmacSha256 as Mac>::new_from_slice(&self.signing).expect("hmac accepts keys of any length");
+        mac.update(&(name.len() as u64).to_be_bytes());
+        mac.update(name.as_bytes());
+        mac.update(value.as_bytes());
+        mac
+    }
+    // Creates the cipher used to encrypt and decrypt values
+    fn cipher(&self) -> Aes256Gcm {
+        Aes256Gcm::new_from_slice(&self.encryption).expect("the encryption key is 32 bytes")
+    }
+}
+
+impl fmt::Debug for Key {
+    // the key is secret, so it isn't written out
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Key {{ .. }}")
+    }
+}
+
+/* ============================================================================================ */
+/*     Cookie Jar                                                                               */
+/* ============================================================================================ */
+
+/// A CookieJar signs and encrypts the values of cookies so that they can't be changed by the
+/// client, and verifies and decrypts them when they are sent back. The jar is only available
+/// with the secure-cookies feature.
+/// 
+/// A signed cookie can be read by the client but not changed, the value is prefixed with an
+/// HMAC-SHA256 signature of the name and value. An encrypted cookie can be neither read nor 
+/// changed, the value is encrypted with AES-256-GCM using the name as associated data. In both 
+/// cases the name is part of the signature, so the value of one cookie can't be moved to another.
+/// 
+/// Keys can be rotated by creating the jar with the new key and adding the old keys with 
+/// with_previous_key. Cookies are always signed and encrypted with the newest key, and are
+/// verified and decrypted with any of the keys.
+/// # Example
+/// ```
+/// use http::request;
+/// use http::response;
+/// use http_tools::cookie::{CookieJar, Key, SetCookie};
+/// 
+/// let jar = CookieJar::new(Key::derive(b"a secret that is at least 32 bytes long"));
+/// 
+/// // sign a cookie when building a response
+/// let mut builder = response::Builder::new();
+/// jar.append_signed(&mut builder, SetCookie::new("user", "42").with_http_only(true));
+/// jar.append_encrypted(&mut builder, SetCookie::new("role", "admin"));
+/// let response = builder.body(()).unwrap();
+/// 
+/// // when the client sends the cookies back they can be verified
+/// let mut cookies = response.headers().get_all("set-cookie").iter()
+///     .map(|header| header.to_str().unwrap().split(';').next().unwrap());
+/// let request = request::Builder::new()
+///     .header("cookie", cookies.next().unwrap())
+///     .header("cookie", cookies.next().unwrap())
+///     .body(()).unwrap();
+/// assert_eq!(jar.get_signed(&request, "user").unwrap(), "42");
+/// assert_eq!(jar.get_encrypted(&request, "role").unwrap(), "admin");
+/// 
+/// // a cookie that has been changed doesn't verify
+/// let request = request::Builder::new()
+///     .header("cookie", "user=tampered")
+///     .body(()).unwrap();
+/// assert_eq!(jar.get_signed(&request, "user"), None);
+/// ```
+#[derive(Clone)]
+pub struct CookieJar {
+    keys : Vec<Key>,
+}
+
+impl CookieJar {
+    /// Creates a jar that signs and encrypts cookies with the key
+    pub fn new(key : Key) -> Self {
+        CookieJar { keys : vec![key] }
+    }
+    /// Adds a key that was used before the current key, cookies signed or encrypted with it will
+    /// still be accepted. Keys are tried in the order they were added.
+    pub fn with_previous_key(mut self, key : Key) -> Self {
+        self.keys.push(key);
+        self
+    }
+    /// Signs the value of the cookie with the name, the signed value is returned
+    pub fn sign(&self, name : &str, value : &str) -> String {
+        let signature = self.keys[0].mac(name, value).finalize().into_bytes();
+        let mut signed = URL_SAFE_NO_PAD.encode(signature);
+        signed.push_str(value);
+        signed
+    }
+    /// Verifies a signed value of the cookie with the name, and returns the value without its
+    /// signature. The value is percent decoded first, as SetCookie encodes values that contain
+    /// characters that aren't allowed in a cookie. None is returned if the signature doesn't 
+    /// match any of the keys.
+    pub fn verify<'v>(&self, name : &str, value : &'v str) -> Option<Cow<'v, str>> {
+        let value = percent_decode(value);
+        if value.len() < SIGNATURE_LEN || !value.is_char_boundary(SIGNATURE_LEN) {
+            return None;
+        }
+        let signature = URL_SAFE_NO_PAD.decode(&value[..SIGNATURE_LEN]).ok()?;
+        let verified = self.keys.iter()
+            .any(|key| key.mac(name, &value[SIGNATURE_LEN..]).verify_slice(&signature).is_ok());
+        if !verified {
+            return None;
+        }
+        // return the value without the signature, borrowing it if it wasn't decoded
+        match value {
+            Cow::Borrowed(value) => Some(Cow::Borrowed(&value[SIGNATURE_LEN..])),
+            Cow::Owned(value) => Some(Cow::Owned(value[SIGNATURE_LEN..].to_string())),
+        }
+    }
+    /// Encrypts the value of the cookie with the name, the encrypted value is returned. A random
+    /// nonce is used, so encrypting the same value twice gives different results.
+    pub fn encrypt(&self, name : &str, value : &str) -> String {
+        let cipher = self.keys[0].cipher();
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let encrypted = cipher.encrypt(&nonce, Payload { msg : value.as_bytes(), aad : name.as_bytes() })
+            .expect("encryption only fails for values that are far too large for a cookie");
+        let mut sealed = nonce.to_vec();
+        sealed.extend_from_slice(&encrypted);
+        URL_SAFE_NO_PAD.encode(sealed)
+    }
+    /// Decrypts an encrypted value of the cookie with the name. None is returned if the value
+    /// can't be decrypted by any of the keys, which means it was changed or encrypted for a 
+    /// different name.
+    pub fn decrypt(&self, name : &str, value : &str) -> Option<String> {
+        let sealed = URL_SAFE_NO_PAD.decode(percent_decode(value).as_bytes()).ok()?;
+        if sealed.len() < NONCE_LEN + TAG_LEN {
+            return None;
+        }
+        let (nonce, encrypted) = sealed.split_at(NONCE_LEN);
+        let decrypted = self.keys.iter().find_map(|key| {
+            key.cipher().decrypt(nonce.into(), Payload { msg : encrypted, aad : name.as_bytes() }).ok()
+        })?;
+        String::from_utf8(decrypted).ok()
+    }
+    /// Signs the value of the cookie and appends it to the response as a Set-Cookie header, the
+    /// attributes of the cookie are kept
+    pub fn append_signed<'b>(&self, builder : &'b mut Builder, cookie : SetCookie) -> &'b mut Builder {
+        let value = self.sign(cookie.name(), cookie.value());
+        cookie.with_value(&value).append_to(builder)
+    }
+    /// Encrypts the value of the cookie and appends it to the response as a Set-Cookie header,
+    /// the attributes of the cookie are kept
+    pub fn append_encrypted<'b>(&self, builder : &'b mut Builder, cookie : SetCookie) -> &'b mut Builder {
+        let value = self.encrypt(cookie.name(), cookie.value());
+        cookie.with_value(&value).append_to(builder)
+    }
+    /// Returns the value of the first cookie of the request with the name that has a valid
+    /// signature, cookies with the name that don't verify are skipped
+    pub fn get_signed<'r, R>(&self, request : &'r Request<R>, name : &str) -> Option<Cow<'r, str>> {
+        cookie_iter(request)
+            .filter(|(c_name, _)| *c_name == name)
+            .find_map(|(_, value)| self.verify(name, value))
+    }
+    /// Returns the decrypted value of the first cookie of the request with the name that can be
+    /// decrypted, cookies with the name that can't be decrypted are skipped
+    pub fn get_encrypted<R>(&self, request : &Request<R>, name : &str) -> Option<String> {
+        cookie_iter(request)
+            .filter(|(c_name, _)| *c_name == name)
+            .find_map(|(_, value)| self.decrypt(name, value))
+    }
+}
+
+impl fmt::Debug for CookieJar {
+    // the keys are secret, so only the number of keys is written out
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CookieJar").field("keys", &self.keys.len()).finish()
+    }
+}
+
+/* ============================================================================================ */
+/*     Test Cases                                                                               */
+/* ============================================================================================ */
+#[cfg(test)]
+fn test_jar(secret : u8) -> CookieJar {
+    CookieJar::new(Key::derive(&[secret; 32]))
+}
+
+#[test]
+fn test_signed() {
+    let jar = test_jar(1);
+    let signed = jar.sign("user", "42");
+    assert_eq!(signed.len(), SIGNATURE_LEN + 2);
+    assert_eq!(jar.verify("user", &signed), Some(Cow::Borrowed("42")));
+    assert_eq!(jar.verify("admin", &signed), None);
+    assert_eq!(jar.verify("user", &signed.replace("42", "43")), None);
+    assert_eq!(jar.verify("user", "42"), None);
+    assert_eq!(test_jar(2).verify("user", &signed), None);
+
+    // values that are percent encoded by SetCookie verify once decoded
+    let signed = jar.sign("name", "John Doe; 100%");
+    let cookie = SetCookie::new("name", &signed).to_string();
+    let written = &cookie["name=".len()..];
+    assert_eq!(jar.verify("name", written).unwrap(), "John Doe; 100%");
+}
+
+#[test]
+fn test_encrypted() {
+    let jar = test_jar(1);
+    let encrypted = jar.encrypt("role", "admin");
+    assert!(!encrypted.contains("admin"));
+    assert_ne!(encrypted, jar.encrypt("role", "admin"));
+    assert_eq!(jar.decrypt("role", &encrypted), Some("admin".to_string()));
+    assert_eq!(jar.decrypt("user", &encrypted), None);
+    assert_eq!(test_jar(2).decrypt("role", &encrypted), None);
+    let mut tampered = encrypted.into_bytes();
+    let last = tampered.len() - 1;
+    tampered[last] = if tampered[last] == b'A' { b'B' } else { b'A' };
+    assert_eq!(jar.decrypt("role", std::str::from_utf8(&tampered).unwrap()), None);
+    assert_eq!(jar.decrypt("role", "short"), None);
+    assert_eq!(jar.decrypt("role", "not base64!"), None);
+}
+
+#[test]
+fn test_key_rotation() {
+    let old = test_jar(1);
+    let new = CookieJar::new(Key::derive(&[2; 32])).with_previous_key(Key::derive(&[1; 32]));
+    let signed = old.sign("user", "42");
+    let encrypted = old.encrypt("role", "admin");
+    assert_eq!(new.verify("user", &signed).unwrap(), "42");
+    assert_eq!(new.decrypt("role", &encrypted).unwrap(), "admin");
+    // new cookies use the newest key, so the old jar can't read them
+    assert_eq!(old.verify("user", &new.sign("user", "42")), None);
+    assert_eq!(old.decrypt("role", &new.encrypt("role", "admin")), None);
+}
+
+#[test]
+fn test_jar_request() {
+    use http::request;
+    let jar = test_jar(1);
+    let cookie = format!("user=forged; user={}; role={}", jar.sign("user", "42"), jar.encrypt("role", "admin"));
+    let request = request::Builder::new().header("cookie", cookie).body(()).unwrap();
+    assert_eq!(jar.get_signed(&request, "user").unwrap(), "42");
+    assert_eq!(jar.get_encrypted(&request, "role").unwrap(), "admin");
+    assert_eq!(jar.get_signed(&request, "role"), None);
+    assert_eq!(jar.get_encrypted(&request, "missing"), None);
+}
+
+#[test]
+#[should_panic]
+fn test_short_secret() {
+    Key::derive(b"too short");
+}
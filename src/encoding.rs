@@ -0,0 +1,120 @@
+// MIT License
+// 
+// Copyright (c) 2019 Jonathon Davis
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software. 
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+/*! 
+ Provides functions for decoding the percent encoding used by uris.
+
+ The functions return a `Cow<str>` so that when there is nothing to decode the input is
+ borrowed rather than copied, an allocation is only made when the input actually changes.
+*/
+use std::borrow::Cow;
+
+/// Decodes the percent encoded bytes in the input, such as %20 into a space. A '%' that isn't
+/// followed by two hex digits is left as it is, and any decoded bytes that aren't valid utf-8
+/// are replaced with the replacement character. This is the decoding used for uri paths, where
+/// a '+' is a literal plus sign.
+/// # Example
+/// ```
+/// use std::borrow::Cow;
+/// use http_tools::encoding::percent_decode;
+/// 
+/// assert_eq!(percent_decode("John%20Doe"), "John Doe");
+/// assert_eq!(percent_decode("a+b"), "a+b");
+/// 
+/// // nothing needed decoding so the input was borrowed
+/// assert!(matches!(percent_decode("John"), Cow::Borrowed("John")));
+/// ```
+pub fn percent_decode(input : &str) -> Cow<'_, str> {
+    decode(input, false)
+}
+
+/// Decodes a component of a query string, this is the same as percent_decode except that a '+'
+/// is decoded into a space as is done by application/x-www-form-urlencoded.
+/// # Example
+/// ```
+/// use http_tools::encoding::query_decode;
+/// 
+/// assert_eq!(query_decode("John+Doe"), "John Doe");
+/// assert_eq!(query_decode("John%20Doe"), "John Doe");
+/// assert_eq!(query_decode("1%2B1"), "1+1");
+/// ```
+pub fn query_decode(input : &str) -> Cow<'_, str> {
+    decode(input, true)
+}
+
+// decode borrows the input if there is nothing to decode, otherwise it decodes
+// the input into a new string
+fn decode(input : &str, plus_as_space : bool) -> Cow<'_, str> {
+    let needs_decoding = input.bytes().any(|b| b == b'%' || (plus_as_space && b == b'+'));
+    if !needs_decoding {
+        return Cow::Borrowed(input);
+    }
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            // a percent followed by two hex digits is an encoded byte
+            b'%' if index + 2 < bytes.len() => {
+                match (hex_value(bytes[index + 1]), hex_value(bytes[index + 2])) {
+                    (Some(high), Some(low)) => {
+                        decoded.push(high << 4 | low);
+                        index += 3;
+                        continue;
+                    }
+                    _ => decoded.push(b'%'),
+                }
+            }
+            b'+' if plus_as_space => decoded.push(b' '),
+            byte => decoded.push(byte),
+        }
+        index += 1;
+    }
+    Cow::Owned(String::from_utf8(decoded).unwrap_or_else(|err| String::from_utf8_lossy(err.as_bytes()).into_owned()))
+}
+
+// Converts an ascii hex digit into its value
+fn hex_value(byte : u8) -> Option<u8> {
+    match byte {
+        b'0'..=b'9' => Some(byte - b'0'),
+        b'a'..=b'f' => Some(byte - b'a' + 10),
+        b'A'..=b'F' => Some(byte - b'A' + 10),
+        _ => None,
+    }
+}
+
+#[test]
+fn test_percent_decode() {
+    assert_eq!(percent_decode("plain"), Cow::Borrowed("plain"));
+    assert_eq!(percent_decode("a%20b%2fc"), "a b/c");
+    assert_eq!(percent_decode("a+b"), "a+b");
+    assert_eq!(percent_decode("100%"), "100%");
+    assert_eq!(percent_decode("%zz%4"), "%zz%4");
+    assert_eq!(percent_decode("%C3%A9t%C3%A9"), "\u{e9}t\u{e9}");
+    assert_eq!(percent_decode("%FF"), "\u{fffd}");
+}
+
+#[test]
+fn test_query_decode() {
+    assert_eq!(query_decode("plain"), Cow::Borrowed("plain"));
+    assert_eq!(query_decode("John+Doe"), "John Doe");
+    assert_eq!(query_decode("a%2Bb"), "a+b");
+}
@@ -0,0 +1,110 @@
+// MIT License
+// 
+// Copyright (c) 2019 Jonathon Davis
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software. 
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+/* ============================================================================================ */
+/*     Document Structure                                                                       */
+/*          Authorization                                                                       */
+/*          Test Cases                                                                          */
+/* ============================================================================================ */
+use std::fmt;
+use crate::header::is_token;
+
+/* ============================================================================================ */
+/*     Authorization                                                                            */
+/* ============================================================================================ */
+
+/// The credentials of an Authorization or Proxy-Authorization header, made up of an 
+/// authentication scheme such as Basic or Bearer followed by the credentials. Both are borrowed
+/// from the header value, and the credentials are left encoded.
+/// # Example
+/// ```
+/// use http_tools::header::Authorization;
+/// 
+/// let authorization = Authorization::parse("Basic QWxhZGRpbjpvcGVuIHNlc2FtZQ==").unwrap();
+/// assert!(authorization.is_scheme("basic"));
+/// assert_eq!(authorization.credentials(), "QWxhZGRpbjpvcGVuIHNlc2FtZQ==");
+/// assert_eq!(authorization.bearer(), None);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Authorization<'a> {
+    scheme : &'a str,
+    credentials : &'a str,
+}
+
+impl<'a> Authorization<'a> {
+    /// Parses the value of an Authorization header, None is returned if the scheme isn't a token
+    pub fn parse(value : &'a str) -> Option<Self> {
+        let value = value.trim();
+        let (scheme, credentials) = match value.find(' ') {
+            Some(space) => (&value[..space], value[space + 1..].trim_start()),
+            None => (value, ""),
+        };
+        if !is_token(scheme) {
+            return None;
+        }
+        Some(Authorization { scheme, credentials })
+    }
+    /// Returns the authentication scheme, such as Basic or Bearer
+    pub fn scheme(&self) -> &'a str {
+        self.scheme
+    }
+    /// Returns the credentials that follow the scheme, which may be empty
+    pub fn credentials(&self) -> &'a str {
+        self.credentials
+    }
+    /// Checks the authentication scheme without regard to case
+    pub fn is_scheme(&self, scheme : &str) -> bool {
+        self.scheme.eq_ignore_ascii_case(scheme)
+    }
+    /// Returns the token if the scheme is Bearer and a token was given
+    pub fn bearer(&self) -> Option<&'a str> {
+        match self.is_scheme("bearer") && !self.credentials.is_empty() {
+            true => Some(self.credentials),
+            false => None,
+        }
+    }
+}
+
+impl<'a> fmt::Display for Authorization<'a> {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        match self.credentials.is_empty() {
+            true => write!(f, "{}", self.scheme),
+            false => write!(f, "{} {}", self.scheme, self.credentials),
+        }
+    }
+}
+
+/* ============================================================================================ */
+/*     Test Cases                                                                               */
+/* ============================================================================================ */
+#[test]
+fn test_authorization_parse() {
+    let authorization = Authorization::parse("Bearer  abc.def").unwrap();
+    assert_eq!(authorization.scheme(), "Bearer");
+    assert_eq!(authorization.credentials(), "abc.def");
+    assert_eq!(authorization.bearer(), Some("abc.def"));
+    assert_eq!(authorization.to_string(), "Bearer abc.def");
+    let authorization = Authorization::parse("Negotiate").unwrap();
+    assert_eq!(authorization.credentials(), "");
+    assert_eq!(Authorization::parse("bearer").unwrap().bearer(), None);
+    assert!(Authorization::parse("").is_none());
+    assert!(Authorization::parse("B@d token").is_none());
+}
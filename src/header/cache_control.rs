@@ -0,0 +1,266 @@
+// MIT License
+// 
+// Copyright (c) 2019 Jonathon Davis
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software. 
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+/* ============================================================================================ */
+/*     Document Structure                                                                       */
+/*          Cache Control                                                                       */
+/*          Test Cases                                                                          */
+/* ============================================================================================ */
+use std::fmt;
+use std::time::Duration;
+use crate::header::{split_unquoted, unquote};
+
+/* ============================================================================================ */
+/*     Cache Control                                                                            */
+/* ============================================================================================ */
+
+/// The directives of a Cache-Control header. Both request and response directives are parsed,
+/// unknown directives and directives with invalid values are ignored. The type can also be used
+/// to build a Cache-Control header, as it is written out as a list of its directives.
+/// # Example
+/// ```
+/// use std::time::Duration;
+/// use http_tools::header::CacheControl;
+/// 
+/// let cache_control = CacheControl::parse("no-cache, max-age=0, private=\"set-cookie\"");
+/// assert!(cache_control.no_cache());
+/// assert!(cache_control.private());
+/// assert_eq!(cache_control.max_age(), Some(Duration::from_secs(0)));
+/// 
+/// // or to build a header value
+/// let cache_control = CacheControl::default().with_public(true).with_max_age(Duration::from_secs(60));
+/// assert_eq!(cache_control.to_string(), "public, max-age=60");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CacheControl {
+    no_cache : bool,
+    no_store : bool,
+    no_transform : bool,
+    only_if_cached : bool,
+    must_revalidate : bool,
+    proxy_revalidate : bool,
+    public : bool,
+    private : bool,
+    immutable : bool,
+    max_age : Option<u64>,
+    s_maxage : Option<u64>,
+    max_stale : Option<u64>,
+    min_fresh : Option<u64>,
+}
+
+impl CacheControl {
+    /// Parses the directives of a single Cache-Control value
+    pub fn parse(value : &str) -> Self {
+        split_unquoted(value, b',')
+            .map(str::trim)
+            .filter(|directive| !directive.is_empty())
+            .fold(CacheControl::default(), |cache_control, directive| cache_control.with_directive(directive))
+    }
+    // Adds a single directive such as max-age=60 to the CacheControl
+    pub(crate) fn with_directive(mut self, directive : &str) -> Self {
+        let (name, value) = match directive.find('=') {
+            Some(equals) => (directive[..equals].trim(), Some(unquote(directive[equals + 1..].trim()))),
+            None => (directive, None),
+        };
+        // the seconds of a directive like max-age, invalid values cause the directive to be ignored
+        let seconds = value.and_then(|value| value.parse::<u64>().ok());
+        // directive names are compared without regard to case
+        let is = |directive : &str| name.eq_ignore_ascii_case(directive);
+        match () {
+            _ if is("no-cache") => self.no_cache = true,
+            _ if is("no-store") => self.no_store = true,
+            _ if is("no-transform") => self.no_transform = true,
+            _ if is("only-if-cached") => self.only_if_cached = true,
+            _ if is("must-revalidate") => self.must_revalidate = true,
+            _ if is("proxy-revalidate") => self.proxy_revalidate = true,
+            _ if is("public") => self.public = true,
+            _ if is("private") => self.private = true,
+            _ if is("immutable") => self.immutable = true,
+            _ if is("max-age") => self.max_age = seconds.or(self.max_age),
+            _ if is("s-maxage") => self.s_maxage = seconds.or(self.s_maxage),
+            _ if is("min-fresh") => self.min_fresh = seconds.or(self.min_fresh),
+            // a max-stale without a value means any staleness is accepted
+            _ if is("max-stale") => self.max_stale = if value.is_none() { Some(u64::MAX) } else { seconds.or(self.max_stale) },
+            _ => (),
+        }
+        self
+    }
+    /// Returns true if the no-cache directive is present
+    pub fn no_cache(&self) -> bool {
+        self.no_cache
+    }
+    /// Returns true if the no-store directive is present
+    pub fn no_store(&self) -> bool {
+        self.no_store
+    }
+    /// Returns true if the no-transform directive is present
+    pub fn no_transform(&self) -> bool {
+        self.no_transform
+    }
+    /// Returns true if the only-if-cached directive is present
+    pub fn only_if_cached(&self) -> bool {
+        self.only_if_cached
+    }
+    /// Returns true if the must-revalidate directive is present
+    pub fn must_revalidate(&self) -> bool {
+        self.must_revalidate
+    }
+    /// Returns true if the proxy-revalidate directive is present
+    pub fn proxy_revalidate(&self) -> bool {
+        self.proxy_revalidate
+    }
+    /// Returns true if the public directive is present
+    pub fn public(&self) -> bool {
+        self.public
+    }
+    /// Returns true if the private directive is present, with or without a list of fields
+    pub fn private(&self) -> bool {
+        self.private
+    }
+    /// Returns true if the immutable directive is present
+    pub fn immutable(&self) -> bool {
+        self.immutable
+    }
+    /// Returns the value of the max-age directive
+    pub fn max_age(&self) -> Option<Duration> {
+        self.max_age.map(Duration::from_secs)
+    }
+    /// Returns the value of the s-maxage directive
+    pub fn s_maxage(&self) -> Option<Duration> {
+        self.s_maxage.map(Duration::from_secs)
+    }
+    /// Returns the value of the max-stale directive, a max-stale without a value returns the
+    /// largest possible duration
+    pub fn max_stale(&self) -> Option<Duration> {
+        self.max_stale.map(Duration::from_secs)
+    }
+    /// Returns the value of the min-fresh directive
+    pub fn min_fresh(&self) -> Option<Duration> {
+        self.min_fresh.map(Duration::from_secs)
+    }
+
+    /// Sets the no-cache directive
+    pub fn with_no_cache(mut self, no_cache : bool) -> Self {
+        self.no_cache = no_cache;
+        self
+    }
+    /// Sets the no-store directive
+    pub fn with_no_store(mut self, no_store : bool) -> Self {
+        self.no_store = no_store;
+        self
+    }
+    /// Sets the no-transform directive
+    pub fn with_no_transform(mut self, no_transform : bool) -> Self {
+        self.no_transform = no_transform;
+        self
+    }
+    /// Sets the must-revalidate directive
+    pub fn with_must_revalidate(mut self, must_revalidate : bool) -> Self {
+        self.must_revalidate = must_revalidate;
+        self
+    }
+    /// Sets the public directive
+    pub fn with_public(mut self, public : bool) -> Self {
+        self.public = public;
+        self
+    }
+    /// Sets the private directive
+    pub fn with_private(mut self, private : bool) -> Self {
+        self.private = private;
+        self
+    }
+    /// Sets the immutable directive
+    pub fn with_immutable(mut self, immutable : bool) -> Self {
+        self.immutable = immutable;
+        self
+    }
+    /// Sets the max-age directive, rounded down to the second
+    pub fn with_max_age(mut self, max_age : Duration) -> Self {
+        self.max_age = Some(max_age.as_secs());
+        self
+    }
+    /// Sets the s-maxage directive, rounded down to the second
+    pub fn with_s_maxage(mut self, s_maxage : Duration) -> Self {
+        self.s_maxage = Some(s_maxage.as_secs());
+        self
+    }
+}
+
+impl fmt::Display for CacheControl {
+    // writes each directive that is present seperated by a comma
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        let flags = [
+            (self.no_cache, "no-cache"), (self.no_store, "no-store"), (self.no_transform, "no-transform"),
+            (self.only_if_cached, "only-if-cached"), (self.must_revalidate, "must-revalidate"),
+            (self.proxy_revalidate, "proxy-revalidate"), (self.public, "public"), (self.private, "private"),
+            (self.immutable, "immutable"),
+        ];
+        let seconds = [("max-age", self.max_age), ("s-maxage", self.s_maxage), ("max-stale", self.max_stale), ("min-fresh", self.min_fresh)];
+        let mut first = true;
+        let mut separator = |f : &mut fmt::Formatter| {
+            let result = if first { Ok(()) } else { write!(f, ", ") };
+            first = false;
+            result
+        };
+        for (_, name) in flags.iter().filter(|(present, _)| *present) {
+            separator(f)?;
+            write!(f, "{}", name)?;
+        }
+        for (name, value) in seconds.iter() {
+            match value {
+                Some(u64::MAX) if *name == "max-stale" => { separator(f)?; write!(f, "{}", name)?; }
+                Some(value) => { separator(f)?; write!(f, "{}={}", name, value)?; }
+                None => (),
+            }
+        }
+        Ok(())
+    }
+}
+
+/* ============================================================================================ */
+/*     Test Cases                                                                               */
+/* ============================================================================================ */
+#[test]
+fn test_cache_control_parse() {
+    let cache_control = CacheControl::parse("No-Store, max-age=\"30\", s-maxage=abc, max-stale, unknown=1");
+    assert!(cache_control.no_store());
+    assert!(!cache_control.no_cache());
+    assert_eq!(cache_control.max_age(), Some(Duration::from_secs(30)));
+    assert_eq!(cache_control.s_maxage(), None);
+    assert_eq!(cache_control.max_stale(), Some(Duration::from_secs(u64::MAX)));
+    assert_eq!(cache_control.to_string(), "no-store, max-age=30, max-stale");
+    assert_eq!(CacheControl::parse(""), CacheControl::default());
+    assert_eq!(CacheControl::default().to_string(), "");
+}
+
+#[test]
+fn test_cache_control_headers() {
+    use http::response::Builder;
+    use crate::header::cache_control;
+    let response = Builder::new()
+        .header("cache-control", "public")
+        .header("cache-control", "max-age=60, immutable")
+        .body(()).unwrap();
+    let cache_control = cache_control(response.headers());
+    assert!(cache_control.public() && cache_control.immutable());
+    assert_eq!(cache_control.max_age(), Some(Duration::from_secs(60)));
+    assert_eq!(cache_control.to_string(), "public, immutable, max-age=60");
+}
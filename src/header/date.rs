@@ -0,0 +1,289 @@
+// MIT License
+// 
+// Copyright (c) 2019 Jonathon Davis
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software. 
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+/* ============================================================================================ */
+/*     Document Structure                                                                       */
+/*          Http Date                                                                           */
+/*          Calendar Math                                                                       */
+/*          Test Cases                                                                          */
+/* ============================================================================================ */
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const WEEKDAYS : [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+const LONG_WEEKDAYS : [&str; 7] = ["Sunday", "Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday"];
+const MONTHS : [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+
+/* ============================================================================================ */
+/*     Http Date                                                                                */
+/* ============================================================================================ */
+
+/// A date as used by headers such as Date, Last-Modified and Expires, which is always in GMT
+/// and has a precision of one second. Dates can be parsed from any of the three formats allowed
+/// by RFC 7231, and are always written in the preferred IMF-fixdate format. HttpDate can be
+/// converted to and from a SystemTime, and dates can be compared.
+/// # Example
+/// ```
+/// use std::time::{Duration, UNIX_EPOCH, SystemTime};
+/// use http_tools::header::HttpDate;
+/// 
+/// // all three formats are parsed
+/// let date = HttpDate::parse("Sun, 06 Nov 1994 08:49:37 GMT").unwrap();
+/// assert_eq!(HttpDate::parse("Sunday, 06-Nov-94 08:49:37 GMT"), Some(date));
+/// assert_eq!(HttpDate::parse("Sun Nov  6 08:49:37 1994"), Some(date));
+/// 
+/// // converting to and from a SystemTime
+/// let time = SystemTime::from(date);
+/// assert_eq!(time, UNIX_EPOCH + Duration::from_secs(784111777));
+/// assert_eq!(HttpDate::from(time), date);
+/// 
+/// // dates are written as an IMF-fixdate
+/// let date = HttpDate::from(UNIX_EPOCH);
+/// assert_eq!(date.to_string(), "Thu, 01 Jan 1970 00:00:00 GMT");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HttpDate {
+    year : u16,
+    month : u8,
+    day : u8,
+    hour : u8,
+    minute : u8,
+    second : u8,
+}
+
+impl HttpDate {
+    /// Parses a date in the IMF-fixdate, RFC 850 or asctime format. The weekday must be a
+    /// valid name, but isn't checked against the date. None is returned if the date can't be
+    /// parsed or doesn't exist.
+    pub fn parse(value : &str) -> Option<Self> {
+        let value = value.trim();
+        let date = match value.find(',') {
+            Some(comma) => {
+                let weekday = &value[..comma];
+                if !WEEKDAYS.iter().chain(LONG_WEEKDAYS.iter()).any(|name| name.eq_ignore_ascii_case(weekday)) {
+                    return None;
+                }
+                let mut parts = value[comma + 1..].split_whitespace();
+                match (parts.next(), parts.next(), parts.next(), parts.next(), parts.next(), parts.next()) {
+                    // IMF-fixdate, Sun, 06 Nov 1994 08:49:37 GMT
+                    (Some(day), Some(month), Some(year), Some(time), Some("GMT"), None) => {
+                        HttpDate::from_parts(number(year, 4, 4)?, month, day, time)?
+                    }
+                    // RFC 850, Sunday, 06-Nov-94 08:49:37 GMT
+                    (Some(date), Some(time), Some("GMT"), None, None, None) => {
+                        let mut date = date.split('-');
+                        let (day, month, year) = (date.next()?, date.next()?, date.next()?);
+                        if date.next().is_some() {
+                            return None;
+                        }
+                        // two digit years are assumed to be between 1970 and 2069
+                        let year = number(year, 2, 2)?;
+                        let year = if year < 70 { 2000 + year } else { 1900 + year };
+                        HttpDate::from_parts(year, month, day, time)?
+                    }
+                    _ => return None,
+                }
+            }
+            None => {
+                // asctime, Sun Nov  6 08:49:37 1994
+                let mut parts = value.split_whitespace();
+                match (parts.next(), parts.next(), parts.next(), parts.next(), parts.next(), parts.next()) {
+                    (Some(weekday), Some(month), Some(day), Some(time), Some(year), None) => {
+                        if !WEEKDAYS.iter().any(|name| name.eq_ignore_ascii_case(weekday)) {
+                            return None;
+                        }
+                        HttpDate::from_parts(number(year, 4, 4)?, month, day, time)?
+                    }
+                    _ => return None,
+                }
+            }
+        };
+        Some(date)
+    }
+    // Builds a date from the parts shared by every format, checking that the date exists
+    fn from_parts(year : u32, month : &str, day : &str, time : &str) -> Option<Self> {
+        let month = MONTHS.iter().position(|name| name.eq_ignore_ascii_case(month))? as u8 + 1;
+        let day = number(day, 1, 2)? as u8;
+        if day == 0 || day > days_in_month(year as u16, month) {
+            return None;
+        }
+        let mut time = time.split(':');
+        let (hour, minute, second) = (number(time.next()?, 2, 2)?, number(time.next()?, 2, 2)?, number(time.next()?, 2, 2)?);
+        if time.next().is_some() || hour > 23 || minute > 59 || second > 59 {
+            return None;
+        }
+        Some(HttpDate { year : year as u16, month, day, hour : hour as u8, minute : minute as u8, second : second as u8 })
+    }
+    /// Returns the year, such as 1994
+    pub fn year(&self) -> u16 {
+        self.year
+    }
+    /// Returns the month, from 1 for January to 12 for December
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+    /// Returns the day of the month, starting from 1
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+    /// Returns the hour, from 0 to 23
+    pub fn hour(&self) -> u8 {
+        self.hour
+    }
+    /// Returns the minute, from 0 to 59
+    pub fn minute(&self) -> u8 {
+        self.minute
+    }
+    /// Returns the second, from 0 to 59
+    pub fn second(&self) -> u8 {
+        self.second
+    }
+    // Returns the number of seconds since the unix epoch, which is negative for earlier dates
+    fn unix_seconds(&self) -> i64 {
+        let days = days_from_civil(i64::from(self.year), i64::from(self.month), i64::from(self.day));
+        days * 86400 + i64::from(self.hour) * 3600 + i64::from(self.minute) * 60 + i64::from(self.second)
+    }
+}
+
+impl From<SystemTime> for HttpDate {
+    // the time is rounded down to the second, and times past the year 9999 are capped as the
+    // year can only be written with four digits
+    fn from(time : SystemTime) -> Self {
+        let seconds = match time.duration_since(UNIX_EPOCH) {
+            Ok(duration) => duration.as_secs().min(253_402_300_799) as i64,
+            Err(err) => -(err.duration().as_secs() as i64) - i64::from(err.duration().subsec_nanos() > 0),
+        };
+        let (days, seconds) = (seconds.div_euclid(86400), seconds.rem_euclid(86400));
+        let (year, month, day) = civil_from_days(days);
+        HttpDate {
+            year : year.max(0) as u16,
+            month : month as u8,
+            day : day as u8,
+            hour : (seconds / 3600) as u8,
+            minute : (seconds / 60 % 60) as u8,
+            second : (seconds % 60) as u8,
+        }
+    }
+}
+
+impl From<HttpDate> for SystemTime {
+    fn from(date : HttpDate) -> Self {
+        let seconds = date.unix_seconds();
+        match seconds >= 0 {
+            true => UNIX_EPOCH + Duration::from_secs(seconds as u64),
+            false => UNIX_EPOCH - Duration::from_secs(seconds.unsigned_abs()),
+        }
+    }
+}
+
+impl fmt::Display for HttpDate {
+    // writes the date as an IMF-fixdate such as Sun, 06 Nov 1994 08:49:37 GMT
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        // the unix epoch was a Thursday
+        let weekday = (self.unix_seconds().div_euclid(86400) + 4).rem_euclid(7) as usize;
+        write!(f, "{}, {:02} {} {:04} {:02}:{:02}:{:02} GMT",
+            WEEKDAYS[weekday], self.day, MONTHS[self.month as usize - 1], self.year, self.hour, self.minute, self.second)
+    }
+}
+
+/* ============================================================================================ */
+/*     Calendar Math                                                                            */
+/* ============================================================================================ */
+
+// Parses a number that has between min and max digits, signs aren't allowed
+fn number(value : &str, min : usize, max : usize) -> Option<u32> {
+    if value.len() < min || value.len() > max || !value.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    value.parse().ok()
+}
+
+// Returns the number of days in the month of the year
+fn days_in_month(year : u16, month : u8) -> u8 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// Returns the number of days since the unix epoch for the date in the proleptic gregorian
+// calendar, this and civil_from_days are the algorithms described by Howard Hinnant in
+// "chrono-Compatible Low-Level Date Algorithms"
+fn days_from_civil(year : i64, month : i64, day : i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+// Returns the year, month and day for the number of days since the unix epoch
+fn civil_from_days(days : i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/* ============================================================================================ */
+/*     Test Cases                                                                               */
+/* ============================================================================================ */
+#[test]
+fn test_date_parse() {
+    let date = HttpDate::parse("Sun, 06 Nov 1994 08:49:37 GMT").unwrap();
+    assert_eq!((date.year(), date.month(), date.day()), (1994, 11, 6));
+    assert_eq!((date.hour(), date.minute(), date.second()), (8, 49, 37));
+    assert_eq!(HttpDate::parse("Thursday, 01-Jan-70 00:00:00 GMT"), Some(HttpDate::from(UNIX_EPOCH)));
+    assert_eq!(HttpDate::parse("Monday, 01-Jan-69 00:00:00 GMT").unwrap().year(), 2069);
+    assert_eq!(HttpDate::parse("Thu Feb 29 12:00:00 2024").unwrap().day(), 29);
+    assert!(HttpDate::parse("Thu Feb 29 12:00:00 2023").is_none());
+    assert!(HttpDate::parse("Sun, 06 Nov 1994 08:49:37 UTC").is_none());
+    assert!(HttpDate::parse("Sun, 06 Nov 1994 24:00:00 GMT").is_none());
+    assert!(HttpDate::parse("Sun, 31 Nov 1994 08:49:37 GMT").is_none());
+    assert!(HttpDate::parse("Sun, 06 Foo 1994 08:49:37 GMT").is_none());
+    assert!(HttpDate::parse("Funday, 06 Nov 1994 08:49:37 GMT").is_none());
+    assert!(HttpDate::parse("").is_none());
+}
+
+#[test]
+fn test_date_conversion() {
+    for seconds in &[0i64, 784_111_777, 951_782_400, 1_709_208_000, 4_102_444_799, -2_208_988_800, -1] {
+        let time = match *seconds >= 0 {
+            true => UNIX_EPOCH + Duration::from_secs(*seconds as u64),
+            false => UNIX_EPOCH - Duration::from_secs(seconds.unsigned_abs()),
+        };
+        let date = HttpDate::from(time);
+        assert_eq!(SystemTime::from(date), time);
+        assert_eq!(HttpDate::parse(&date.to_string()), Some(date));
+    }
+    assert_eq!(HttpDate::from(UNIX_EPOCH - Duration::from_secs(1)).to_string(), "Wed, 31 Dec 1969 23:59:59 GMT");
+    assert_eq!(HttpDate::from(UNIX_EPOCH + Duration::from_secs(951_782_400)).to_string(), "Tue, 29 Feb 2000 00:00:00 GMT");
+    assert!(HttpDate::parse("Sun, 06 Nov 1994 08:49:37 GMT") < HttpDate::parse("Mon, 07 Nov 1994 00:00:00 GMT"));
+}
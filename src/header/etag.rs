@@ -0,0 +1,137 @@
+// MIT License
+// 
+// Copyright (c) 2019 Jonathon Davis
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software. 
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+/* ============================================================================================ */
+/*     Document Structure                                                                       */
+/*          Entity Tag                                                                          */
+/*          Test Cases                                                                          */
+/* ============================================================================================ */
+use std::fmt;
+
+/* ============================================================================================ */
+/*     Entity Tag                                                                               */
+/* ============================================================================================ */
+
+/// An entity tag as found in the ETag, If-Match and If-None-Match headers. The tag is borrowed
+/// from the header value without its quotes.
+/// # Example
+/// ```
+/// use http_tools::header::ETag;
+/// 
+/// let strong = ETag::parse("\"xyzzy\"").unwrap();
+/// let weak = ETag::parse("W/\"xyzzy\"").unwrap();
+/// 
+/// // a weak comparison ignores whether the tags are weak, a strong comparison requires both
+/// // tags to be strong
+/// assert!(strong.weak_eq(&weak));
+/// assert!(!strong.strong_eq(&weak));
+/// assert!(strong.strong_eq(&strong));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ETag<'a> {
+    weak : bool,
+    tag : &'a str,
+}
+
+impl<'a> ETag<'a> {
+    /// Parses an entity tag such as "xyzzy" or W/"xyzzy", None is returned if the tag isn't
+    /// quoted or contains characters that aren't allowed
+    pub fn parse(value : &'a str) -> Option<Self> {
+        let value = value.trim();
+        let (weak, quoted) = match value.starts_with("W/") {
+            true => (true, &value[2..]),
+            false => (false, value),
+        };
+        if quoted.len() < 2 || !quoted.starts_with('"') || !quoted.ends_with('"') {
+            return None;
+        }
+        let tag = &quoted[1..quoted.len() - 1];
+        // etagc is any visible character other than a quote, or any non ascii byte
+        if !tag.bytes().all(|b| b == 0x21 || (0x23..=0x7e).contains(&b) || b >= 0x80) {
+            return None;
+        }
+        Some(ETag { weak, tag })
+    }
+    /// Creates a strong entity tag, the tag should not contain quotes
+    pub fn strong(tag : &'a str) -> Self {
+        ETag { weak : false, tag }
+    }
+    /// Creates a weak entity tag, the tag should not contain quotes
+    pub fn weak(tag : &'a str) -> Self {
+        ETag { weak : true, tag }
+    }
+    /// Returns the tag without its quotes
+    pub fn tag(&self) -> &'a str {
+        self.tag
+    }
+    /// Returns true if the tag is weak
+    pub fn is_weak(&self) -> bool {
+        self.weak
+    }
+    /// The strong comparison used by If-Match, both tags must be strong and the same
+    pub fn strong_eq(&self, other : &ETag) -> bool {
+        !self.weak && !other.weak && self.tag == other.tag
+    }
+    /// The weak comparison used by If-None-Match, the tags must be the same
+    pub fn weak_eq(&self, other : &ETag) -> bool {
+        self.tag == other.tag
+    }
+}
+
+impl<'a> fmt::Display for ETag<'a> {
+    // writes the tag with its quotes, and the W/ prefix if it is weak
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        match self.weak {
+            true => write!(f, "W/\"{}\"", self.tag),
+            false => write!(f, "\"{}\"", self.tag),
+        }
+    }
+}
+
+/* ============================================================================================ */
+/*     Test Cases                                                                               */
+/* ============================================================================================ */
+#[test]
+fn test_etag_parse() {
+    assert_eq!(ETag::parse("\"xyzzy\""), Some(ETag::strong("xyzzy")));
+    assert_eq!(ETag::parse(" W/\"xyzzy\" "), Some(ETag::weak("xyzzy")));
+    assert_eq!(ETag::parse("\"\""), Some(ETag::strong("")));
+    assert_eq!(ETag::parse("\"a,b\"").unwrap().tag(), "a,b");
+    assert!(ETag::parse("xyzzy").is_none());
+    assert!(ETag::parse("w/\"xyzzy\"").is_none());
+    assert!(ETag::parse("\"xy\"zzy\"").is_none());
+    assert!(ETag::parse("\"").is_none());
+    assert!(ETag::parse("*").is_none());
+    assert_eq!(ETag::weak("xyzzy").to_string(), "W/\"xyzzy\"");
+    assert_eq!(ETag::strong("xyzzy").to_string(), "\"xyzzy\"");
+}
+
+#[test]
+fn test_etag_iter() {
+    use http::request::Builder;
+    use crate::header::etag_iter;
+    let request = Builder::new()
+        .header("if-none-match", "\"a\", W/\"b,c\"")
+        .header("if-none-match", "*")
+        .body(()).unwrap();
+    let tags = etag_iter(request.headers(), "if-none-match").collect::<Vec<_>>();
+    assert_eq!(tags, vec![ETag::strong("a"), ETag::weak("b,c")]);
+}
@@ -0,0 +1,171 @@
+// MIT License
+// 
+// Copyright (c) 2019 Jonathon Davis
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software. 
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+/* ============================================================================================ */
+/*     Document Structure                                                                       */
+/*          Media Type                                                                          */
+/*          Test Cases                                                                          */
+/* ============================================================================================ */
+use std::fmt;
+use crate::header::{is_token, split_unquoted, unquote};
+
+/* ============================================================================================ */
+/*     Media Type                                                                               */
+/* ============================================================================================ */
+
+/// A MediaType such as text/html; charset=utf-8, as found in the Content-Type and Accept 
+/// headers. The type, subtype and parameters are borrowed from the header value. The type and
+/// subtype are compared without regard to case, as are the names of parameters.
+/// # Example
+/// ```
+/// use http_tools::header::{MediaType, mime};
+/// 
+/// let media_type = MediaType::parse("application/ld+json; charset=\"utf-8\"").unwrap();
+/// assert_eq!(media_type.type_(), "application");
+/// assert_eq!(media_type.subtype(), "ld+json");
+/// assert_eq!(media_type.suffix(), Some("json"));
+/// assert_eq!(media_type.charset(), Some("utf-8"));
+/// 
+/// // is ignores the parameters and accepts wildcards
+/// assert!(media_type.is("application/LD+JSON"));
+/// assert!(media_type.is("application/*"));
+/// assert!(!media_type.is(mime::JSON));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MediaType<'a> {
+    essence : &'a str,
+    slash : usize,
+    params : &'a str,
+}
+
+impl<'a> MediaType<'a> {
+    /// Parses a media type, None is returned if the type or subtype is missing or isn't a token
+    pub fn parse(value : &'a str) -> Option<Self> {
+        let essence = split_unquoted(value, b';').next()?;
+        let params = value.get(essence.len() + 1..).unwrap_or("");
+        let essence = essence.trim();
+        let slash = essence.find('/')?;
+        if !is_token(&essence[..slash]) || !is_token(&essence[slash + 1..]) {
+            return None;
+        }
+        Some(MediaType { essence, slash, params })
+    }
+    /// Returns the type and subtype without any parameters, such as text/html
+    pub fn essence(&self) -> &'a str {
+        self.essence
+    }
+    /// Returns the type, such as text in text/html
+    pub fn type_(&self) -> &'a str {
+        &self.essence[..self.slash]
+    }
+    /// Returns the subtype, such as html in text/html
+    pub fn subtype(&self) -> &'a str {
+        &self.essence[self.slash + 1..]
+    }
+    /// Returns the structured syntax suffix of the subtype, such as json in application/ld+json
+    pub fn suffix(&self) -> Option<&'a str> {
+        let subtype = self.subtype();
+        subtype.rfind('+').map(|plus| &subtype[plus + 1..])
+    }
+    /// Returns an iterator over the names and values of the parameters, quoted values have their
+    /// quotes removed. Parameters without an '=' are skipped.
+    pub fn params(&self) -> impl 'a + Iterator<Item=(&'a str, &'a str)> {
+        split_unquoted(self.params, b';').filter_map(|param| {
+            let equals = param.find('=')?;
+            Some((param[..equals].trim(), unquote(param[equals + 1..].trim())))
+        })
+    }
+    /// Returns the value of the first parameter with the name, the name is compared without
+    /// regard to case
+    pub fn param(&self, name : &str) -> Option<&'a str> {
+        self.params().find(|(key, _)| key.eq_ignore_ascii_case(name)).map(|(_, value)| value)
+    }
+    /// Returns the value of the charset parameter
+    pub fn charset(&self) -> Option<&'a str> {
+        self.param("charset")
+    }
+    /// Checks if this media type is the given media type, ignoring the parameters of both.
+    /// The given media type can use a '*' wildcard for the subtype, or for both the type and
+    /// subtype, so text/* matches any text media type.
+    pub fn is(&self, media_type : &str) -> bool {
+        let (type_, subtype) = match MediaType::parse(media_type) {
+            Some(media_type) => (media_type.type_(), media_type.subtype()),
+            None => return false,
+        };
+        match (type_, subtype) {
+            ("*", "*") => true,
+            ("*", _) => false,
+            (type_, "*") => type_.eq_ignore_ascii_case(self.type_()),
+            (type_, subtype) => type_.eq_ignore_ascii_case(self.type_()) && subtype.eq_ignore_ascii_case(self.subtype()),
+        }
+    }
+    /// Returns true if the type or subtype is the '*' wildcard, as used in the Accept header
+    pub fn is_wildcard(&self) -> bool {
+        self.type_() == "*" || self.subtype() == "*"
+    }
+}
+
+impl<'a> fmt::Display for MediaType<'a> {
+    // writes the media type as it was parsed, with the parameters unchanged
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        match self.params.is_empty() {
+            true => write!(f, "{}", self.essence),
+            false => write!(f, "{};{}", self.essence, self.params),
+        }
+    }
+}
+
+/* ============================================================================================ */
+/*     Test Cases                                                                               */
+/* ============================================================================================ */
+#[test]
+fn test_media_type_parse() {
+    let media_type = MediaType::parse(" text/html ; charset=utf-8;format=\"a;b\"").unwrap();
+    assert_eq!(media_type.essence(), "text/html");
+    assert_eq!(media_type.type_(), "text");
+    assert_eq!(media_type.subtype(), "html");
+    assert_eq!(media_type.suffix(), None);
+    assert_eq!(media_type.params().collect::<Vec<_>>(), vec![("charset", "utf-8"), ("format", "a;b")]);
+    assert_eq!(media_type.param("CHARSET"), Some("utf-8"));
+    assert_eq!(media_type.param("missing"), None);
+    assert_eq!(media_type.to_string(), "text/html; charset=utf-8;format=\"a;b\"");
+    assert_eq!(MediaType::parse("text/plain").unwrap().to_string(), "text/plain");
+    assert!(MediaType::parse("text").is_none());
+    assert!(MediaType::parse("text/").is_none());
+    assert!(MediaType::parse("/html").is_none());
+    assert!(MediaType::parse("te xt/html").is_none());
+    assert!(MediaType::parse("").is_none());
+}
+
+#[test]
+fn test_media_type_is() {
+    let media_type = MediaType::parse("Application/JSON; charset=utf-8").unwrap();
+    assert!(media_type.is("application/json"));
+    assert!(media_type.is("application/json; charset=ascii"));
+    assert!(media_type.is("application/*"));
+    assert!(media_type.is("*/*"));
+    assert!(!media_type.is("text/*"));
+    assert!(!media_type.is("*/json"));
+    assert!(!media_type.is("application/xml"));
+    assert!(!media_type.is("invalid"));
+    assert!(!media_type.is_wildcard());
+    assert!(MediaType::parse("text/*").unwrap().is_wildcard());
+}
@@ -0,0 +1,64 @@
+// MIT License
+// 
+// Copyright (c) 2019 Jonathon Davis
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software. 
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+/*! 
+ Constants for commonly used media types, for use with filters such as filter_content_type.
+ The constants don't include any parameters.
+*/
+
+/// Any media type
+pub const ANY : &str = "*/*";
+/// JSON, application/json
+pub const JSON : &str = "application/json";
+/// MessagePack, application/msgpack
+pub const MSGPACK : &str = "application/msgpack";
+/// XML, application/xml
+pub const XML : &str = "application/xml";
+/// Url encoded forms, application/x-www-form-urlencoded
+pub const FORM : &str = "application/x-www-form-urlencoded";
+/// Multipart forms, multipart/form-data
+pub const MULTIPART_FORM : &str = "multipart/form-data";
+/// Arbitrary binary data, application/octet-stream
+pub const OCTET_STREAM : &str = "application/octet-stream";
+/// PDF documents, application/pdf
+pub const PDF : &str = "application/pdf";
+/// Plain text, text/plain
+pub const TEXT : &str = "text/plain";
+/// HTML, text/html
+pub const HTML : &str = "text/html";
+/// CSS, text/css
+pub const CSS : &str = "text/css";
+/// CSV, text/csv
+pub const CSV : &str = "text/csv";
+/// JavaScript, text/javascript
+pub const JAVASCRIPT : &str = "text/javascript";
+/// Server sent events, text/event-stream
+pub const EVENT_STREAM : &str = "text/event-stream";
+/// PNG images, image/png
+pub const PNG : &str = "image/png";
+/// JPEG images, image/jpeg
+pub const JPEG : &str = "image/jpeg";
+/// GIF images, image/gif
+pub const GIF : &str = "image/gif";
+/// SVG images, image/svg+xml
+pub const SVG : &str = "image/svg+xml";
+/// WebP images, image/webp
+pub const WEBP : &str = "image/webp";
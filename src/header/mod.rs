@@ -0,0 +1,384 @@
+// MIT License
+// 
+// Copyright (c) 2019 Jonathon Davis
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software. 
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+/*! 
+ Provides typed parsing for the values of common headers.
+
+ Each function takes a HeaderMap, so the same function works on both a Request and a Response,
+ and returns a type that borrows from the header value where it can. A header that is missing
+ or can't be parsed results in None rather than an error, in the same way that a filter that
+ doesn't match results in None.
+*/
+/* ============================================================================================ */
+/*     Document Structure                                                                       */
+/*          Header Functions                                                                    */
+/*          List Splitting                                                                      */
+/*          Test Cases                                                                          */
+/* ============================================================================================ */
+pub mod mime;
+mod media_type;
+pub(crate) mod quality;
+mod authorization;
+mod date;
+mod etag;
+mod cache_control;
+pub use self::{
+    media_type::MediaType,
+    quality::Quality,
+    authorization::Authorization,
+    date::HttpDate,
+    etag::ETag,
+    cache_control::CacheControl,
+};
+use http::header::{
+    AsHeaderName, HeaderMap, HeaderValue, ACCEPT, AUTHORIZATION, CACHE_CONTROL, CONTENT_LENGTH,
+    CONTENT_TYPE, ETAG, PROXY_AUTHORIZATION,
+};
+
+/* ============================================================================================ */
+/*     Header Functions                                                                         */
+/* ============================================================================================ */
+
+/// Returns the media type of the Content-Type header
+/// # Example
+/// ```
+/// use http::request::Builder;
+/// use http_tools::header::{content_type, mime};
+/// 
+/// let request = Builder::new()
+///                 .header("content-type", "application/json; charset=utf-8")
+///                 .body(()).unwrap();
+/// 
+/// let media_type = content_type(request.headers()).unwrap();
+/// assert!(media_type.is(mime::JSON));
+/// assert_eq!(media_type.charset(), Some("utf-8"));
+/// ```
+pub fn content_type(headers : &HeaderMap) -> Option<MediaType<'_>> {
+    header_str(headers, CONTENT_TYPE).and_then(MediaType::parse)
+}
+
+/// Returns the value of the Content-Length header. A Content-Length may be repeated, either as
+/// multiple headers or as a list, as long as every value is the same. Any other repetition, or a
+/// value that isn't a number, results in None.
+/// # Example
+/// ```
+/// use http::request::Builder;
+/// use http_tools::header::content_length;
+/// 
+/// let request = Builder::new()
+///                 .header("content-length", "42")
+///                 .body(()).unwrap();
+/// assert_eq!(content_length(request.headers()), Some(42));
+/// ```
+pub fn content_length(headers : &HeaderMap) -> Option<u64> {
+    let mut length = None;
+    for value in list_iter(headers, CONTENT_LENGTH) {
+        if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let value = value.parse::<u64>().ok()?;
+        match length {
+            Some(length) if length != value => return None,
+            _ => length = Some(value),
+        }
+    }
+    length
+}
+
+/// Returns an iterator over the media ranges of every Accept header along with their quality,
+/// the q parameter is removed from the parameters of the media range. Elements that can't be
+/// parsed are skipped. 
+/// # Example
+/// ```
+/// use http::request::Builder;
+/// use http_tools::header::{accept, Quality};
+/// 
+/// let request = Builder::new()
+///                 .header("accept", "text/html, application/json;q=0.5")
+///                 .body(()).unwrap();
+/// 
+/// let ranges = accept(request.headers()).collect::<Vec<_>>();
+/// assert_eq!(ranges[0].0.essence(), "text/html");
+/// assert_eq!(ranges[0].1, Quality::ONE);
+/// assert_eq!(ranges[1].0.essence(), "application/json");
+/// assert_eq!(ranges[1].1.thousandths(), 500);
+/// ```
+pub fn accept(headers : &HeaderMap) -> impl Iterator<Item=(MediaType<'_>, Quality)> {
+    list_iter(headers, ACCEPT).filter_map(|element| {
+        let (range, quality) = quality::split_quality(element)?;
+        Some((MediaType::parse(range)?, quality))
+    })
+}
+
+/// Returns the credentials of the Authorization header
+/// # Example
+/// ```
+/// use http::request::Builder;
+/// use http_tools::header::authorization;
+/// 
+/// let request = Builder::new()
+///                 .header("authorization", "Bearer mF_9.B5f-4.1JqM")
+///                 .body(()).unwrap();
+/// assert_eq!(authorization(request.headers()).unwrap().bearer(), Some("mF_9.B5f-4.1JqM"));
+/// ```
+pub fn authorization(headers : &HeaderMap) -> Option<Authorization<'_>> {
+    header_str(headers, AUTHORIZATION).and_then(Authorization::parse)
+}
+
+/// Returns the credentials of the Proxy-Authorization header
+pub fn proxy_authorization(headers : &HeaderMap) -> Option<Authorization<'_>> {
+    header_str(headers, PROXY_AUTHORIZATION).and_then(Authorization::parse)
+}
+
+/// Returns the date held by a header such as Date, Last-Modified, Expires or If-Modified-Since
+/// # Example
+/// ```
+/// use http::request::Builder;
+/// use http_tools::header::http_date;
+/// 
+/// let request = Builder::new()
+///                 .header("if-modified-since", "Sun, 06 Nov 1994 08:49:37 GMT")
+///                 .body(()).unwrap();
+/// 
+/// let date = http_date(request.headers(), "if-modified-since").unwrap();
+/// assert_eq!(date.year(), 1994);
+/// ```
+pub fn http_date<K : AsHeaderName>(headers : &HeaderMap, key : K) -> Option<HttpDate> {
+    header_str(headers, key).and_then(HttpDate::parse)
+}
+
+/// Returns the entity tag of the ETag header
+/// # Example
+/// ```
+/// use http::response::Builder;
+/// use http_tools::header::etag;
+/// 
+/// let response = Builder::new()
+///                 .header("etag", "W/\"xyzzy\"")
+///                 .body(()).unwrap();
+/// 
+/// let tag = etag(response.headers()).unwrap();
+/// assert!(tag.is_weak());
+/// assert_eq!(tag.tag(), "xyzzy");
+/// ```
+pub fn etag(headers : &HeaderMap) -> Option<ETag<'_>> {
+    header_str(headers, ETAG).and_then(ETag::parse)
+}
+
+/// Returns an iterator over the entity tags of a list header such as If-Match or If-None-Match,
+/// the '*' value doesn't name an entity tag and is skipped, as are values that can't be parsed.
+pub fn etag_iter<K : AsHeaderName>(headers : &HeaderMap, key : K) -> impl Iterator<Item=ETag<'_>> {
+    list_iter(headers, key).filter_map(ETag::parse)
+}
+
+/// Returns the directives of every Cache-Control header combined into one, unknown directives
+/// are ignored. A missing header results in a CacheControl without any directives.
+/// # Example
+/// ```
+/// use std::time::Duration;
+/// use http::response::Builder;
+/// use http_tools::header::cache_control;
+/// 
+/// let response = Builder::new()
+///                 .header("cache-control", "public, max-age=3600")
+///                 .body(()).unwrap();
+/// 
+/// let cache_control = cache_control(response.headers());
+/// assert!(cache_control.public());
+/// assert_eq!(cache_control.max_age(), Some(Duration::from_secs(3600)));
+/// ```
+pub fn cache_control(headers : &HeaderMap) -> CacheControl {
+    list_iter(headers, CACHE_CONTROL).fold(CacheControl::default(), |cache_control, directive| cache_control.with_directive(directive))
+}
+
+// Returns the first value of the header as a str, values that aren't visible ascii are ignored
+fn header_str<K : AsHeaderName>(headers : &HeaderMap, key : K) -> Option<&str> {
+    headers.get(key).and_then(|value| value.to_str().ok())
+}
+
+/* ============================================================================================ */
+/*     List Splitting                                                                           */
+/* ============================================================================================ */
+
+/// Returns an iterator over the elements of a comma separated list header such as Connection
+/// or Cache-Control. Every value of a repeated header is split, so the elements of each header
+/// are returned in order. Elements are trimmed, empty elements are skipped and commas inside of
+/// a quoted string don't split the element. Values that aren't visible ascii are skipped.
+/// # Example
+/// ```
+/// use http::request::Builder;
+/// use http_tools::header::list_iter;
+/// 
+/// let request = Builder::new()
+///                 .header("connection", "keep-alive, Upgrade")
+///                 .header("connection", "close")
+///                 .body(()).unwrap();
+/// 
+/// let elements = list_iter(request.headers(), "connection").collect::<Vec<_>>();
+/// assert_eq!(elements, vec!["keep-alive", "Upgrade", "close"]);
+/// ```
+pub fn list_iter<K : AsHeaderName>(headers : &HeaderMap, key : K) -> impl Iterator<Item=&str> {
+    headers.get_all(key)
+        .into_iter()
+        .filter_map(|value : &HeaderValue| value.to_str().ok())
+        .flat_map(|value| split_unquoted(value, b','))
+        .map(str::trim)
+        .filter(|element| !element.is_empty())
+}
+
+// Checks if any element of the list header is the value, used by filter_header_contains on both
+// the request and response. The wildcard '{}' can be used for either the key or the value.
+pub(crate) fn list_contains(headers : &HeaderMap, key : &str, value : &str, ignore_case : bool) -> bool {
+    let matches = |element : &str| match ignore_case {
+        _ if value == "{}" => true,
+        true => element.eq_ignore_ascii_case(value),
+        false => element == value,
+    };
+    match key {
+        "{}" => headers.keys().any(|key| list_iter(headers, key).any(matches)),
+        key => list_iter(headers, key).any(matches),
+    }
+}
+
+// Splits the value on the separator, ignoring any separators inside of a quoted string
+pub(crate) fn split_unquoted(value : &str, separator : u8) -> SplitUnquoted<'_> {
+    SplitUnquoted { rest : Some(value), separator }
+}
+
+// The iterator returned by split_unquoted
+pub(crate) struct SplitUnquoted<'a> {
+    rest : Option<&'a str>,
+    separator : u8,
+}
+
+impl<'a> Iterator for SplitUnquoted<'a> {
+    type Item = &'a str;
+    fn next(&mut self) -> Option<&'a str> {
+        let rest = self.rest?;
+        let mut quoted = false;
+        let mut escaped = false;
+        for (index, byte) in rest.bytes().enumerate() {
+            match byte {
+                // the character after a backslash in a quoted string is taken literally
+                _ if escaped => escaped = false,
+                b'\\' if quoted => escaped = true,
+                b'"' => quoted = !quoted,
+                byte if byte == self.separator && !quoted => {
+                    self.rest = Some(&rest[index + 1..]);
+                    return Some(&rest[..index]);
+                }
+                _ => (),
+            }
+        }
+        self.rest = None;
+        Some(rest)
+    }
+}
+
+// Removes the quotes surrounding a quoted string, a value that isn't quoted is returned as is.
+// Escaped characters are left escaped so the value can be borrowed.
+pub(crate) fn unquote(value : &str) -> &str {
+    if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+        &value[1..value.len() - 1]
+    } else {
+        value
+    }
+}
+
+// Checks that the value is a token as defined by RFC 7230
+pub(crate) fn is_token(value : &str) -> bool {
+    !value.is_empty() && value.bytes().all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b))
+}
+
+/* ============================================================================================ */
+/*     Test Cases                                                                               */
+/* ============================================================================================ */
+#[test]
+fn test_split_unquoted() {
+    let split = split_unquoted("a, \"b, c\", d\\,e", b',').collect::<Vec<_>>();
+    assert_eq!(split, vec!["a", " \"b, c\"", " d\\", "e"]);
+    let split = split_unquoted("\"a\\\",b\",c", b',').collect::<Vec<_>>();
+    assert_eq!(split, vec!["\"a\\\",b\"", "c"]);
+    assert_eq!(split_unquoted("", b',').collect::<Vec<_>>(), vec![""]);
+}
+
+#[test]
+fn test_list_iter() {
+    use http::request::Builder;
+    let request = Builder::new()
+        .header("cache-control", "no-cache, ,max-age=0")
+        .header("cache-control", "private")
+        .body(()).unwrap();
+    let list = list_iter(request.headers(), "cache-control").collect::<Vec<_>>();
+    assert_eq!(list, vec!["no-cache", "max-age=0", "private"]);
+}
+
+#[test]
+fn test_content_length() {
+    use http::request::Builder;
+    let length = |values : &[&str]| {
+        let mut builder = Builder::new();
+        for value in values {
+            builder.header("content-length", *value);
+        }
+        content_length(builder.body(()).unwrap().headers())
+    };
+    assert_eq!(length(&[]), None);
+    assert_eq!(length(&["0"]), Some(0));
+    assert_eq!(length(&["42", "42"]), Some(42));
+    assert_eq!(length(&["42, 42"]), Some(42));
+    assert_eq!(length(&["42", "43"]), None);
+    assert_eq!(length(&["+42"]), None);
+    assert_eq!(length(&["forty"]), None);
+}
+
+#[test]
+fn test_accept() {
+    use http::request::Builder;
+    let request = Builder::new()
+        .header("accept", "text/*;level=1;q=0.3, invalid, */*;q=0")
+        .body(()).unwrap();
+    let ranges = accept(request.headers()).collect::<Vec<_>>();
+    assert_eq!(ranges.len(), 2);
+    assert_eq!(ranges[0].0.essence(), "text/*");
+    assert_eq!(ranges[0].0.param("level"), Some("1"));
+    assert_eq!(ranges[0].0.param("q"), None);
+    assert_eq!(ranges[0].1.thousandths(), 300);
+    assert!(ranges[1].1.is_zero());
+}
+
+#[test]
+fn test_list_contains() {
+    use http::request::Builder;
+    let request = Builder::new()
+        .header("connection", "keep-alive, Upgrade")
+        .header("x-list", "\"a, b\"")
+        .body(()).unwrap();
+    let headers = request.headers();
+    assert!(list_contains(headers, "connection", "Upgrade", false));
+    assert!(!list_contains(headers, "connection", "upgrade", false));
+    assert!(list_contains(headers, "connection", "upgrade", true));
+    assert!(list_contains(headers, "connection", "{}", false));
+    assert!(list_contains(headers, "{}", "KEEP-ALIVE", true));
+    assert!(list_contains(headers, "x-list", "\"a, b\"", false));
+    assert!(!list_contains(headers, "x-list", "b\"", false));
+    assert!(!list_contains(headers, "missing", "{}", false));
+}
@@ -0,0 +1,158 @@
+// MIT License
+// 
+// Copyright (c) 2019 Jonathon Davis
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software. 
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+/* ============================================================================================ */
+/*     Document Structure                                                                       */
+/*          Quality                                                                             */
+/*          Test Cases                                                                          */
+/* ============================================================================================ */
+use std::fmt;
+use crate::header::split_unquoted;
+
+/* ============================================================================================ */
+/*     Quality                                                                                  */
+/* ============================================================================================ */
+
+/// The quality, or q value, given to an element of a header such as Accept. The quality is
+/// a number between 0 and 1 with at most three decimal places, so it is stored as thousandths
+/// to allow qualities to be compared exactly. A quality of 0 means not acceptable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Quality(u16);
+
+impl Quality {
+    /// The highest quality, this is the quality of an element without a q parameter
+    pub const ONE : Quality = Quality(1000);
+    /// The lowest quality, an element with this quality is not acceptable
+    pub const ZERO : Quality = Quality(0);
+
+    /// Parses a q value such as 0.5, None is returned if the value isn't a valid q value
+    /// # Example
+    /// ```
+    /// use http_tools::header::Quality;
+    /// 
+    /// assert_eq!(Quality::parse("0.5").unwrap().thousandths(), 500);
+    /// assert_eq!(Quality::parse("1.000"), Some(Quality::ONE));
+    /// assert_eq!(Quality::parse("1.5"), None);
+    /// ```
+    pub fn parse(value : &str) -> Option<Self> {
+        let bytes = value.as_bytes();
+        let (whole, fraction) = match bytes {
+            [whole] => (*whole, &[][..]),
+            [whole, b'.', fraction @ ..] if fraction.len() <= 3 => (*whole, fraction),
+            _ => return None,
+        };
+        if !fraction.iter().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        // pad the fraction out to three digits so that 0.5 becomes 500
+        let thousandths = (0..3).fold(0, |total, index| total * 10 + fraction.get(index).map_or(0, |b| u16::from(b - b'0')));
+        match whole {
+            b'0' => Some(Quality(thousandths)),
+            b'1' if thousandths == 0 => Some(Quality::ONE),
+            _ => None,
+        }
+    }
+    /// Creates a quality from thousandths, values above 1000 are capped at 1000
+    pub fn from_thousandths(thousandths : u16) -> Self {
+        Quality(thousandths.min(1000))
+    }
+    /// Returns the quality in thousandths, so a q value of 0.5 returns 500
+    pub fn thousandths(self) -> u16 {
+        self.0
+    }
+    /// Returns true if the quality is 0, meaning not acceptable
+    pub fn is_zero(self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl Default for Quality {
+    fn default() -> Self {
+        Quality::ONE
+    }
+}
+
+impl fmt::Display for Quality {
+    // writes the shortest form of the q value, such as 1, 0.5 or 0.125
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            1000 => write!(f, "1"),
+            0 => write!(f, "0"),
+            thousandths => {
+                // drop the trailing zeros, keeping the leading ones so that 50 is written as 0.05
+                let (mut digits, mut width) = (thousandths, 3);
+                while digits % 10 == 0 {
+                    digits /= 10;
+                    width -= 1;
+                }
+                write!(f, "0.{:0width$}", digits, width = width)
+            }
+        }
+    }
+}
+
+// Splits an element of a list header into the value before the q parameter and the quality,
+// parameters after the q parameter are extensions and are dropped. An element without a q
+// parameter has a quality of 1, and an element with an invalid q value results in None.
+pub(crate) fn split_quality(element : &str) -> Option<(&str, Quality)> {
+    let mut offset = 0;
+    for (index, param) in split_unquoted(element, b';').enumerate() {
+        if index > 0 {
+            if let Some(equals) = param.find('=') {
+                if param[..equals].trim().eq_ignore_ascii_case("q") {
+                    let quality = Quality::parse(param[equals + 1..].trim())?;
+                    return Some((element[..offset - 1].trim_end(), quality));
+                }
+            }
+        }
+        offset += param.len() + 1;
+    }
+    Some((element, Quality::ONE))
+}
+
+/* ============================================================================================ */
+/*     Test Cases                                                                               */
+/* ============================================================================================ */
+#[test]
+fn test_quality_parse() {
+    assert_eq!(Quality::parse("0"), Some(Quality::ZERO));
+    assert_eq!(Quality::parse("1"), Some(Quality::ONE));
+    assert_eq!(Quality::parse("0.8"), Some(Quality(800)));
+    assert_eq!(Quality::parse("0.125"), Some(Quality(125)));
+    assert_eq!(Quality::parse("1.0"), Some(Quality::ONE));
+    assert_eq!(Quality::parse("1.001"), None);
+    assert_eq!(Quality::parse("0.1234"), None);
+    assert_eq!(Quality::parse("0."), Some(Quality::ZERO));
+    assert_eq!(Quality::parse(".5"), None);
+    assert_eq!(Quality::parse(""), None);
+    assert_eq!(Quality(500).to_string(), "0.5");
+    assert_eq!(Quality(125).to_string(), "0.125");
+    assert_eq!(Quality(50).to_string(), "0.05");
+    assert_eq!(Quality::ONE.to_string(), "1");
+}
+
+#[test]
+fn test_split_quality() {
+    assert_eq!(split_quality("text/html"), Some(("text/html", Quality::ONE)));
+    assert_eq!(split_quality("text/html ; q=0.5"), Some(("text/html", Quality(500))));
+    assert_eq!(split_quality("text/html;level=1;Q=0;ext=1"), Some(("text/html;level=1", Quality::ZERO)));
+    assert_eq!(split_quality("gzip;q=2"), None);
+}
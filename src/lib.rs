@@ -32,6 +32,7 @@ allowing for the quick creation of an http router.
 ```
 # use http::request::Builder;
 use http_tools::request::{Extension, Filter};
+use http_tools::header::mime;
 # let request = Builder::new()
 #                .uri("https://www.rust-lang.org/item/rust?cool=rust&also+cool=go")
 #                .extension(-1i32)
@@ -52,10 +53,14 @@ request
     .filter_header("content-type", "application/x-www-form-urlencoded")
     // The {} wild card can be used to filter headers aswell
     .filter_header("content-length", "{}")
+    // typed header filters compare the meaning of a header, so parameters such as the charset are ignored
+    .filter_content_type(mime::FORM)
     // The query has the key cool with the value rust
     .filter_query("cool", "rust")
-    // the wild card {} can be used in queries, filters do not decode uri encodings
+    // the wild card {} can be used in queries, the plain filters compare the raw uri encodings
     .filter_query("also+cool", "{}")
+    // while the _decoded variants decode them first
+    .filter_query_decoded("also cool", "go")
     // custom filters can be applied, and will be given the request and return a bool
     .filter_custom(|req| req.extensions().get::<i32>().is_some())
     // The request has a scheme of https
@@ -97,10 +102,63 @@ fn router(req : &Request<()>) -> Response<()> {
         .filter_path("some_service/{}")
         .filter_method("GET"));
 
-    // Returns method not found
-    Builder::new().status(405).body(()).unwrap()
+    // Returns not found, a Router can be used to tell a 404 apart from a 405
+    Builder::new().status(404).body(()).unwrap()
 }
 ```
+# Router
+When there are a large number of routes, a Router can be used to compile the routes into a tree
+once so that each request is dispatched in a single pass over its path
+```
+# use http::request::Builder;
+use http_tools::request::Router;
+# let request = Builder::new()
+#                .uri("https://www.rust-lang.org/item/42")
+#                .body(()).unwrap();
+
+// the handler can be of any type, the router returns a refrence to it
+let router = Router::new()
+    .route("GET", "/", "index")
+    .route("GET", "/item/{id:u64}", "get_item")
+    .route("POST", "/item", "new_item");
+
+// dispatch returns the handler and the captured path segments
+let found = router.dispatch(&request).unwrap();
+assert_eq!(*found.handler(), "get_item");
+assert_eq!(found.captures().get("id"), Some("42"));
+
+// resolve can tell apart a path that doesn't exist from a method that isn't allowed
+// and builds the matching 404 or 405 response with an Allow header, HEAD and OPTIONS
+// requests are answered from the routes automatically
+if let Some(mut builder) = router.resolve(&request).default_response() {
+    let _response = builder.body(());
+}
+```
+# Routes
+When the routes need to be listed or inspected, Routes keeps them in order as values
+```
+# use http::request::Builder;
+use http_tools::request::{RequestMatcher, Routes};
+# let (health, search) = ("health", "search");
+# let request = Builder::new()
+#                .uri("https://www.rust-lang.org/search?q=rust")
+#                .body(()).unwrap();
+
+// each route is a RequestMatcher, which holds the conditions of a chain of filters as a value
+let routes = Routes::new()
+    .route("health", RequestMatcher::new().with_path("/health").with_method("GET"), health)
+    .route("search", RequestMatcher::new().with_path("/search").with_query("q", "{}"), search);
+
+// the first route whose matcher matches the request is returned
+let route = routes.find(&request).unwrap();
+
+// the routes can be listed, or printed as an aligned table for startup logs
+println!("{}", routes);
+
+// conflicts reports routes that an earlier route shadows, or that overlap ambiguously
+assert!(routes.conflicts().is_empty());
+# assert_eq!(*route.handler(), "search");
+```
 # Iterators
 The crate provides some useful iterators
 ```
@@ -116,9 +174,31 @@ for (key, value) in query_iter(&request){
     println!("{} {}", key, value)
 }
 ```
+# Content Negotiation
+The Accept, Accept-Language and Accept-Encoding headers can be used to pick what to respond with
+```
+# use http::request::Builder;
+use http_tools::request::{accept, accept_encoding, accept_language};
+use http_tools::header::mime;
+# let request = Builder::new()
+#                .header("accept", "text/html;q=0.9, application/json")
+#                .header("accept-language", "fr-CH, en;q=0.5")
+#                .header("accept-encoding", "gzip, br;q=0.5")
+#                .body(()).unwrap();
+
+// each picks the best of the offered values, or None if none are acceptable
+let media_type = accept(&request).negotiate(&[mime::HTML, mime::JSON]);
+let language = accept_language(&request).negotiate(&["en", "fr"]);
+let encoding = accept_encoding(&request).negotiate(&["br", "gzip", "identity"]);
+# assert_eq!((media_type, language, encoding), (Some(mime::JSON), Some("en"), Some("gzip")));
+```
 */
 extern crate http;
 
+pub mod cookie;
+pub mod encoding;
+pub mod header;
+pub mod predicate;
 pub mod request;
 pub mod response;
 mod macros;
@@ -0,0 +1,144 @@
+// MIT License
+// 
+// Copyright (c) 2019 Jonathon Davis
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software. 
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+/*! 
+ Provides the Predicate type, a reusable filter that can capture state.
+*/
+/* ============================================================================================ */
+/*     Document Structure                                                                       */
+/*          Predicate                                                                           */
+/*          Test Cases                                                                          */
+/* ============================================================================================ */
+use std::fmt;
+use std::sync::Arc;
+
+/* ============================================================================================ */
+/*     Predicate                                                                                */
+/* ============================================================================================ */
+
+/// A Predicate is a shared function that tests a value, such as a Request or Response. Unlike 
+/// the fn given to filter_custom, the function can capture state such as configuration loaded at
+/// startup. A Predicate is built once and is cheap to clone, so the same Predicate can be given
+/// to filter_predicate across many routes and threads. Two Predicates are equal when they are 
+/// clones of the same Predicate.
+/// # Example
+/// ```
+/// use http::request::{Builder, Request};
+/// use http_tools::predicate::Predicate;
+/// use http_tools::request::{Extension, Filter};
+/// 
+/// // an allow list that was loaded at startup
+/// let tenants = vec!["acme".to_string(), "globex".to_string()];
+/// let allowed : Predicate<Request<()>> = Predicate::new(move |request : &Request<()>| {
+///     request.headers().get("x-tenant")
+///         .and_then(|tenant| tenant.to_str().ok())
+///         .map_or(false, |tenant| tenants.iter().any(|allowed| allowed == tenant))
+/// });
+/// 
+/// let request = Builder::new()
+///                 .header("x-tenant", "acme")
+///                 .body(()).unwrap();
+/// assert!(request.filter().filter_predicate(&allowed).is_some());
+/// assert!(request.filter().filter_predicate(&allowed.not()).is_none());
+/// ```
+pub struct Predicate<T : ?Sized> {
+    func : Arc<dyn Fn(&T) -> bool + Send + Sync>,
+}
+
+impl<T : ?Sized + 'static> Predicate<T> {
+    /// Creates a Predicate from the function
+    pub fn new<F>(func : F) -> Self where F : Fn(&T) -> bool + Send + Sync + 'static {
+        Predicate { func : Arc::new(func) }
+    }
+    /// Creates a Predicate that passes when both this and the other Predicate pass, the other 
+    /// Predicate is only tested if this one passes
+    pub fn and(&self, other : &Predicate<T>) -> Self {
+        let (first, second) = (self.clone(), other.clone());
+        Predicate::new(move |value| first.test(value) && second.test(value))
+    }
+    /// Creates a Predicate that passes when either this or the other Predicate passes, the other
+    /// Predicate is only tested if this one fails
+    pub fn or(&self, other : &Predicate<T>) -> Self {
+        let (first, second) = (self.clone(), other.clone());
+        Predicate::new(move |value| first.test(value) || second.test(value))
+    }
+    /// Creates a Predicate that passes when this one fails
+    pub fn not(&self) -> Self {
+        let inner = self.clone();
+        Predicate::new(move |value| !inner.test(value))
+    }
+}
+
+impl<T : ?Sized> Predicate<T> {
+    /// Tests the value, returning the result of the function
+    pub fn test(&self, value : &T) -> bool {
+        (self.func)(value)
+    }
+}
+
+// Cloning a Predicate shares the function rather than copying it
+impl<T : ?Sized> Clone for Predicate<T> {
+    fn clone(&self) -> Self {
+        Predicate { func : self.func.clone() }
+    }
+}
+
+// Functions can't be compared, so Predicates are equal only if they share the same function
+impl<T : ?Sized> PartialEq for Predicate<T> {
+    fn eq(&self, other : &Self) -> bool {
+        Arc::ptr_eq(&self.func, &other.func)
+    }
+}
+
+impl<T : ?Sized> Eq for Predicate<T> {}
+
+impl<T : ?Sized> fmt::Debug for Predicate<T> {
+    // the function can't be written out, so the address of the shared function is written instead
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Predicate({:p})", Arc::as_ptr(&self.func) as *const ())
+    }
+}
+
+/* ============================================================================================ */
+/*     Test Cases                                                                               */
+/* ============================================================================================ */
+#[test]
+fn test_predicate() {
+    let limit = 10;
+    let small = Predicate::new(move |value : &i32| *value < limit);
+    let even = Predicate::new(|value : &i32| value % 2 == 0);
+    assert!(small.test(&4));
+    assert!(!small.test(&12));
+    assert!(small.and(&even).test(&4));
+    assert!(!small.and(&even).test(&5));
+    assert!(small.or(&even).test(&12));
+    assert!(!small.or(&even).test(&13));
+    assert!(small.not().test(&12));
+}
+
+#[test]
+fn test_predicate_eq() {
+    let first = Predicate::new(|value : &str| value.is_empty());
+    let second = Predicate::new(|value : &str| value.is_empty());
+    assert_eq!(first, first.clone());
+    assert_ne!(first, second);
+    assert!(format!("{:?}", first).starts_with("Predicate(0x"));
+}
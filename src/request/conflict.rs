@@ -0,0 +1,444 @@
+// MIT License
+// 
+// Copyright (c) 2019 Jonathon Davis
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software. 
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+/* ============================================================================================ */
+/*     Document Structure                                                                       */
+/*          Conflict                                                                            */
+/*          Pattern Analysis                                                                    */
+/*          Test Cases                                                                          */
+/* ============================================================================================ */
+use std::fmt;
+use crate::request::{RequestMatcher, Routes};
+use crate::request::path::{check_constraint, parse_segment, pattern_error, Segment};
+use crate::request::routes::{methods_cell, path_cell};
+
+/* ============================================================================================ */
+/*     Conflict                                                                                 */
+/* ============================================================================================ */
+
+/// The kind of problem found between two routes by Routes::conflicts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictKind {
+    /// Every request the later route matches is also matched by the earlier route, so the later
+    /// route can never be reached
+    Shadowed,
+    /// Some requests match both routes but neither route contains the other, so which route 
+    /// handles those requests depends only on the order the routes were added in
+    Ambiguous,
+}
+
+/// A problem between two routes found by Routes::conflicts. The Display impl describes the
+/// problem along with the methods and path patterns of both routes, so that it can be used as 
+/// the message of a failing test.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    kind : ConflictKind,
+    earlier : (String, String),
+    later : (String, String),
+}
+
+impl Conflict {
+    /// Returns whether the later route is shadowed by the earlier route, or ambiguous with it
+    pub fn kind(&self) -> ConflictKind {
+        self.kind
+    }
+    /// Returns the name of the route that was added first
+    pub fn earlier(&self) -> &str {
+        &self.earlier.0
+    }
+    /// Returns the name of the route that was added second
+    pub fn later(&self) -> &str {
+        &self.later.0
+    }
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        let (earlier, earlier_route) = &self.earlier;
+        let (later, later_route) = &self.later;
+        match self.kind {
+            ConflictKind::Shadowed => write!(f, "route {} ({}) is shadowed by the earlier route {} ({}) and can never match", 
+                later, later_route, earlier, earlier_route),
+            ConflictKind::Ambiguous => write!(f, "routes {} ({}) and {} ({}) are ambiguous, requests that match both go to {}", 
+                earlier, earlier_route, later, later_route, earlier),
+        }
+    }
+}
+
+impl<H> Routes<H> {
+    /// Checks every pair of routes for routes that can never be reached because an earlier route
+    /// matches everything they match, and for routes that overlap without either one containing
+    /// the other. The path patterns, methods, schemes, headers and queries of the matchers are
+    /// compared, but not the requests themselves, so the check is best run from a unit test. 
+    /// Routes that require different headers or queries are assumed to be told apart by them, 
+    /// and are never reported as ambiguous.
+    /// 
+    /// A more specific route added before a broader one, such as /health before /{}, is not a
+    /// conflict since each route can still be reached.
+    /// # Example
+    /// ```
+    /// use http_tools::request::{ConflictKind, RequestMatcher, Routes};
+    /// 
+    /// let routes = Routes::new()
+    ///     .route("health", RequestMatcher::new().with_path("/health").with_method("GET"), ())
+    ///     .route("page", RequestMatcher::new().with_path("/{}").with_method("GET"), ());
+    /// 
+    /// // in a unit test, fail on the first conflict
+    /// for conflict in routes.conflicts() {
+    ///     panic!("{}", conflict);
+    /// }
+    /// 
+    /// // adding the broader route first shadows the health route
+    /// let routes = Routes::new()
+    ///     .route("page", RequestMatcher::new().with_path("/{}").with_method("GET"), ())
+    ///     .route("health", RequestMatcher::new().with_path("/health").with_method("GET"), ());
+    /// 
+    /// let conflicts = routes.conflicts();
+    /// assert_eq!(conflicts[0].kind(), ConflictKind::Shadowed);
+    /// assert_eq!(conflicts[0].to_string(), 
+    ///     "route health (GET /health) is shadowed by the earlier route page (GET /{}) and can never match");
+    /// ```
+    pub fn conflicts(&self) -> Vec<Conflict> {
+        let routes = self.iter().collect::<Vec<_>>();
+        let describe = |index : usize| {
+            let matcher = routes[index].matcher();
+            (routes[index].name().to_string(), format!("{} {}", methods_cell(matcher), path_cell(matcher)))
+        };
+        let mut conflicts = Vec::new();
+        let mut shadowed = vec![false; routes.len()];
+        for later in 0..routes.len() {
+            // a route that is shadowed never handles a request, so it can't be ambiguous either
+            if let Some(earlier) = (0..later).find(|&earlier| !shadowed[earlier] && routes[earlier].matcher().covers(routes[later].matcher())) {
+                shadowed[later] = true;
+                conflicts.push(Conflict { kind : ConflictKind::Shadowed, earlier : describe(earlier), later : describe(later) });
+                continue;
+            }
+            for earlier in (0..later).filter(|&earlier| !shadowed[earlier]) {
+                let (first, second) = (routes[earlier].matcher(), routes[later].matcher());
+                if is_ambiguous(first, second) {
+                    conflicts.push(Conflict { kind : ConflictKind::Ambiguous, earlier : describe(earlier), later : describe(later) });
+                }
+            }
+        }
+        conflicts
+    }
+}
+
+/* ============================================================================================ */
+/*     Pattern Analysis                                                                         */
+/* ============================================================================================ */
+
+// Two routes are ambiguous when they overlap, neither contains the other and they have the same
+// header and query requirements
+fn is_ambiguous(first : &RequestMatcher, second : &RequestMatcher) -> bool {
+    overlaps(first, second) && !covers(second, first) && !covers(first, second)
+        && implies(first, second) && implies(second, first)
+}
+
+// Returns true if every request matched by inner is also matched by outer
+pub(crate) fn covers(outer : &RequestMatcher, inner : &RequestMatcher) -> bool {
+    let scheme = outer.scheme().is_none() || outer.scheme() == inner.scheme();
+    let methods = outer.methods().is_empty() 
+        || (!inner.methods().is_empty() && inner.methods().iter().all(|method| outer.methods().contains(method)));
+    scheme && methods && implies(inner, outer) && match (Pattern::new(outer), Pattern::new(inner)) {
+        (Some(outer), Some(inner)) => outer.covers(&inner),
+        _ => false,
+    }
+}
+
+// Returns true if a request could match both matchers, the header and query requirements are 
+// ignored as a request can have more than one value for the same header or query key
+pub(crate) fn overlaps(first : &RequestMatcher, second : &RequestMatcher) -> bool {
+    let scheme = first.scheme().is_none() || second.scheme().is_none() || first.scheme() == second.scheme();
+    let methods = first.methods().is_empty() || second.methods().is_empty() 
+        || first.methods().iter().any(|method| second.methods().contains(method));
+    scheme && methods && match (Pattern::new(first), Pattern::new(second)) {
+        (Some(first), Some(second)) => first.overlaps(&second),
+        _ => false,
+    }
+}
+
+// Returns true if every header and query requirement of weaker is met by a requirement of stronger,
+// header keys are compared without regard to case
+fn implies(stronger : &RequestMatcher, weaker : &RequestMatcher) -> bool {
+    let meets = |(key, value) : (&str, &str), (s_key, s_value) : (&str, &str), ignore_case : bool| {
+        (key == "{}" || key == s_key || (ignore_case && key.eq_ignore_ascii_case(s_key))) && (value == "{}" || value == s_value)
+    };
+    weaker.headers().all(|header| stronger.headers().any(|s_header| meets(header, s_header, true)))
+        && weaker.queries().all(|query| stronger.queries().any(|s_query| meets(query, s_query, false)))
+}
+
+// The segments that make up the path pattern of a matcher, followed by a tail if the pattern 
+// matches any number of further segments. A tail comes from a prefix or a catch all, the tail of a 
+// prefix that ends in a slash needs at least one further segment, and the tail of a catch all may
+// have a constraint that the rest of the path must satisfy. A matcher without a path has no 
+// segments and a tail, so it matches any path. A pattern that can never match a path has no 
+// Pattern, so it never covers or overlaps another.
+struct Pattern<'a> {
+    segments : Vec<Segment<'a>>,
+    tail : Option<Tail<'a>>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+struct Tail<'a> {
+    min : usize,
+    kind : Option<&'a str>,
+}
+
+impl<'a> Pattern<'a> {
+    fn new(matcher : &'a RequestMatcher) -> Option<Self> {
+        Pattern::parse(matcher.path(), matcher.is_prefix())
+    }
+    fn parse(pattern : Option<&'a str>, prefix : bool) -> Option<Self> {
+        let pattern = match pattern {
+            Some(pattern) => pattern,
+            None => return Some(Pattern { segments : Vec::new(), tail : Some(Tail { min : 0, kind : None }) }),
+        };
+        // the matcher uses filter_path, which doesn't know about any custom constraints
+        if pattern_error(pattern, &[]).is_some() {
+            return None;
+        }
+        let mut segments = Vec::new();
+        for segment in pattern.split('/').map(parse_segment) {
+            match segment {
+                // the catch all is known to be the last segment
+                Segment::CatchAll { kind, .. } => return Some(Pattern { segments, tail : Some(Tail { min : 0, kind }) }),
+                segment => segments.push(segment),
+            }
+        }
+        let tail = match prefix {
+            // the lingering "" of a prefix that ends in a slash stands for at least one more segment
+            true if segments.len() > 1 && matches!(segments.last(), Some(Segment::Literal(""))) => {
+                segments.pop();
+                Some(Tail { min : 1, kind : None })
+            }
+            true => Some(Tail { min : 0, kind : None }),
+            false => None,
+        };
+        Some(Pattern { segments, tail })
+    }
+    // Returns true if every path matched by inner is also matched by self
+    fn covers(&self, inner : &Pattern) -> bool {
+        let (length, inner_length) = (self.segments.len(), inner.segments.len());
+        let same = self.segments.iter().zip(inner.segments.iter()).all(|(segment, inner)| segment_covers(segment, inner));
+        match (self.tail, inner.tail) {
+            (None, None) => same && length == inner_length,
+            (None, Some(_)) => false,
+            // a constrained catch all checks the whole rest of the path, so it only covers the same catch all
+            (Some(tail), inner_tail) if tail.kind.is_some() => same && length == inner_length && inner_tail.map_or(false, |inner_tail| inner_tail.kind == tail.kind && inner_tail.min >= tail.min),
+            // the shortest path inner matches has to be long enough for the tail
+            (Some(tail), inner_tail) => same && inner_length >= length 
+                && inner_length - length + inner_tail.map_or(0, |inner_tail| inner_tail.min) >= tail.min,
+        }
+    }
+    // Returns true if there is a path that both patterns match
+    fn overlaps(&self, other : &Pattern) -> bool {
+        let (short, long) = if self.segments.len() <= other.segments.len() { (self, other) } else { (other, self) };
+        let same = short.segments.iter().zip(long.segments.iter()).all(|(segment, long)| segments_overlap(segment, long));
+        // the tail of the shorter pattern has to match the segments the longer one has left
+        let extra = &long.segments[short.segments.len()..];
+        let fits = |tail : Option<Tail>, rest : &[Segment]| match tail {
+            None => rest.is_empty(),
+            Some(tail) => rest.len() >= tail.min && tail_accepts(tail.kind, rest),
+        };
+        // the path either ends where the longer pattern does, or goes one segment into its tail,
+        // more segments never help as a constrained tail can't match more than one segment
+        let end = fits(short.tail, extra) && fits(long.tail, &[]);
+        let further = long.tail.map_or(false, |tail| {
+            let next = Segment::Wildcard { name : "", kind : tail.kind };
+            let rest : Vec<Segment> = extra.iter().copied().chain(Some(next)).collect();
+            fits(short.tail, &rest) && fits(long.tail, &[next])
+        });
+        same && (end || further)
+    }
+}
+
+// Returns true if there is a rest of the path made up of the segments that the constraint of a
+// tail accepts
+fn tail_accepts(kind : Option<&str>, rest : &[Segment]) -> bool {
+    match (kind, rest) {
+        (None, _) => true,
+        (Some(kind), []) => check_constraint(kind, "", &[]),
+        (Some(kind), [segment]) => segments_overlap(&Segment::Wildcard { name : "", kind : Some(kind) }, segment),
+        // the rest of the path would contain a '/', which no built in constraint accepts
+        (Some(_), _) => false,
+    }
+}
+
+// Returns true if every path segment matched by inner is also matched by outer, the custom 
+// constraints of a Router are unknown here so only the built in constraints are checked
+fn segment_covers(outer : &Segment, inner : &Segment) -> bool {
+    match (outer, inner) {
+        (Segment::Wildcard { kind : None, .. }, _) => true,
+        (Segment::Wildcard { kind : Some(kind), .. }, Segment::Wildcard { kind : Some(inner), .. }) => kind == inner,
+        (Segment::Wildcard { kind : Some(kind), .. }, Segment::Literal(literal)) => check_constraint(kind, literal, &[]),
+        (Segment::Literal(literal), Segment::Literal(inner)) => literal == inner,
+        _ => false,
+    }
+}
+
+// A segment that satisfies each kind of built in constraint. Two built in constraints can match
+// the same segment only if one of these satisfies both of them, the integers all accept 0, alpha,
+// alnum and hex all accept a, and only a uuid can contain a hyphen after the first character.
+const WITNESSES : &[&str] = &["0", "a", "00000000-0000-0000-0000-000000000000"];
+
+// Returns true if there is a path segment that both segments match
+fn segments_overlap(first : &Segment, second : &Segment) -> bool {
+    match (first, second) {
+        (Segment::Literal(first), Segment::Literal(second)) => first == second,
+        (Segment::Wildcard { kind : Some(first), .. }, Segment::Wildcard { kind : Some(second), .. }) => first == second 
+            || WITNESSES.iter().any(|witness| check_constraint(first, witness, &[]) && check_constraint(second, witness, &[])),
+        (Segment::Wildcard { kind : Some(kind), .. }, Segment::Literal(literal)) 
+            | (Segment::Literal(literal), Segment::Wildcard { kind : Some(kind), .. }) => check_constraint(kind, literal, &[]),
+        _ => true,
+    }
+}
+
+/* ============================================================================================ */
+/*     Test Cases                                                                               */
+/* ============================================================================================ */
+#[cfg(test)]
+fn path(pattern : &str) -> RequestMatcher {
+    RequestMatcher::new().with_path(pattern)
+}
+
+#[cfg(test)]
+fn prefix(pattern : &str) -> RequestMatcher {
+    RequestMatcher::new().with_path_prefix(pattern)
+}
+
+#[test]
+fn test_covers() {
+    assert!(covers(&path("/{}"), &path("/health")));
+    assert!(!covers(&path("/health"), &path("/{}")));
+    assert!(covers(&path("/item/{}"), &path("/item/{id:u64}")));
+    assert!(covers(&path("/item/{:u64}"), &path("/item/42")));
+    assert!(!covers(&path("/item/{:u64}"), &path("/item/new")));
+    assert!(!covers(&path("/item/{:u64}"), &path("/item/{}")));
+    assert!(!covers(&path("/{}"), &path("/a/b")));
+    assert!(covers(&prefix("/static"), &path("/static")));
+    assert!(covers(&prefix("/static"), &path("/static/css/{}")));
+    assert!(!covers(&prefix("/static/"), &path("/static")));
+    assert!(covers(&prefix("/static/"), &path("/static/app.js")));
+    assert!(covers(&prefix("/static"), &prefix("/static/")));
+    assert!(!covers(&prefix("/static/"), &prefix("/static")));
+    assert!(covers(&path("/files/{*rest}"), &path("/files/a/b/c")));
+    assert!(covers(&path("/files/{*rest}"), &prefix("/files/a")));
+    assert!(!covers(&path("/files/{*rest:alpha}"), &path("/files/abc")));
+    assert!(covers(&RequestMatcher::new(), &path("/anything")));
+    assert!(!covers(&path("/anything"), &RequestMatcher::new()));
+    assert!(covers(&path("/").with_method("GET").with_method("POST"), &path("/").with_method("GET")));
+    assert!(!covers(&path("/").with_method("GET"), &path("/")));
+    assert!(!covers(&path("/").with_scheme("https"), &path("/")));
+    assert!(covers(&path("/").with_header("accept", "{}"), &path("/").with_header("Accept", "*/*")));
+    assert!(!covers(&path("/").with_query("q", "{}"), &path("/")));
+    assert!(covers(&path("/"), &path("/").with_query("q", "{}")));
+}
+
+#[test]
+fn test_overlaps() {
+    assert!(overlaps(&path("/{}/edit"), &path("/items/{}")));
+    assert!(!overlaps(&path("/items/{}"), &path("/users/{}")));
+    assert!(!overlaps(&path("/items/{}"), &path("/items/{}/{}")));
+    assert!(overlaps(&prefix("/items"), &path("/items/{}/{}")));
+    assert!(!overlaps(&prefix("/items/"), &path("/items")));
+    assert!(overlaps(&path("/{:u64}"), &path("/42")));
+    assert!(!overlaps(&path("/{:u64}"), &path("/new")));
+    assert!(!overlaps(&path("/{:u64}"), &path("/{:alpha}")));
+    assert!(!overlaps(&path("/{:alnum}"), &path("/{:uuid}")));
+    assert!(!overlaps(&path("/{:i8}/{}"), &path("/{:uuid}/{}")));
+    assert!(overlaps(&path("/{:u8}"), &path("/{:i64}")));
+    assert!(overlaps(&path("/{:u64}"), &path("/{:hex}")));
+    assert!(overlaps(&path("/{:alpha}"), &path("/{:alnum}")));
+    assert!(overlaps(&path("/{:uuid}"), &path("/{:uuid}")));
+    assert!(!overlaps(&path("/").with_method("GET"), &path("/").with_method("POST")));
+    assert!(!overlaps(&path("/").with_scheme("http"), &path("/").with_scheme("https")));
+    assert!(overlaps(&path("/").with_scheme("http"), &path("/")));
+    assert!(!overlaps(&path("/f/{*r:u64}"), &path("/f/{*r:alpha}")));
+    assert!(overlaps(&path("/f/{*r:u64}"), &path("/f/{*r:hex}")));
+    assert!(!overlaps(&path("/f/{*r:u64}"), &path("/f/a/b")));
+    assert!(!overlaps(&path("/f/{*r:u64}"), &path("/f/a")));
+    assert!(overlaps(&path("/f/{*r:u64}"), &path("/f/42")));
+    assert!(overlaps(&path("/f/{*r:u64}"), &path("/f/{}")));
+    assert!(!overlaps(&path("/f/{*r:u64}"), &path("/f/{}/{}")));
+    assert!(!overlaps(&path("/f/{*r:u64}"), &path("/f")));
+    assert!(overlaps(&path("/f/{*r:u64}"), &prefix("/f")));
+    assert!(!overlaps(&path("/f/{*r:u64}"), &prefix("/f/a")));
+    assert!(overlaps(&path("/f/{*r}"), &path("/f/a/b")));
+}
+
+#[test]
+fn test_pattern_never_matches() {
+    // a catch all that isn't the last segment never matches, so it can't shadow anything
+    let broken = Pattern::parse(Some("/files/{*rest}/x"), false);
+    assert!(broken.is_none());
+    assert!(Pattern::parse(Some("/{}/{}/{}/{}/{}/{}/{}/{}/{}"), true).is_none());
+    assert!(Pattern::parse(Some("/files/{*rest}"), false).is_some());
+    assert!(Pattern::parse(None, false).is_some());
+}
+
+#[test]
+fn test_witnesses() {
+    use crate::request::path::BUILT_IN_CONSTRAINTS;
+    // any segment that satisfies two built in constraints means a witness has to satisfy both
+    let samples = ["0", "7", "-1", "+1", "300", "-300", "70000", "a", "Z", "f00", "0f", "g1", "abc", "-", "",
+        "12345678901234567890123", "123e4567-e89b-12d3-a456-426614174000", "0000000000000000000000000000000000000000"];
+    for first in BUILT_IN_CONSTRAINTS {
+        for second in BUILT_IN_CONSTRAINTS {
+            let both = |segment : &&str| check_constraint(first, segment, &[]) && check_constraint(second, segment, &[]);
+            if samples.iter().any(both) {
+                assert!(WITNESSES.iter().any(both), "{} and {}", first, second);
+            }
+        }
+    }
+    // a constrained catch all can only match one segment of the path
+    for kind in BUILT_IN_CONSTRAINTS {
+        assert!(!WITNESSES.iter().any(|witness| check_constraint(kind, &format!("{}/{}", witness, witness), &[])), "{}", kind);
+    }
+}
+
+#[test]
+fn test_conflicts() {
+    let routes = Routes::new()
+        .route("health", path("/health").with_method("GET"), ())
+        .route("page", path("/{}").with_method("GET"), ())
+        .route("about", path("/about").with_method("GET"), ())
+        .route("edit", path("/{}/edit"), ())
+        .route("item", path("/items/{}"), ())
+        .route("post_item", path("/items/{}").with_method("POST"), ())
+        .route("json", path("/upload").with_header("content-type", "application/json"), ())
+        .route("form", path("/upload").with_header("content-type", "application/x-www-form-urlencoded"), ());
+    let conflicts = routes.conflicts();
+    assert_eq!(conflicts.len(), 3);
+    assert_eq!((conflicts[0].kind(), conflicts[0].earlier(), conflicts[0].later()), (ConflictKind::Shadowed, "page", "about"));
+    assert_eq!((conflicts[1].kind(), conflicts[1].earlier(), conflicts[1].later()), (ConflictKind::Ambiguous, "edit", "item"));
+    assert_eq!((conflicts[2].kind(), conflicts[2].earlier(), conflicts[2].later()), (ConflictKind::Shadowed, "item", "post_item"));
+    assert_eq!(conflicts[1].to_string(), "routes edit (* /{}/edit) and item (* /items/{}) are ambiguous, requests that match both go to edit");
+    let routes = Routes::new()
+        .route("health", path("/health"), ())
+        .route("static", prefix("/static/"), ())
+        .route("page", path("/{}"), ());
+    assert_eq!(routes.conflicts(), Vec::new());
+    let routes = Routes::new()
+        .route("by_id", path("/users/{:u64}"), ())
+        .route("by_name", path("/users/{:alpha}"), ());
+    assert_eq!(routes.conflicts(), Vec::new());
+}
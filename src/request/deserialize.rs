@@ -0,0 +1,392 @@
+// MIT License
+// 
+// Copyright (c) 2019 Jonathon Davis
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software. 
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+/* ============================================================================================ */
+/*     Document Structure                                                                       */
+/*          Query Deserialization                                                               */
+/*          Query Error                                                                         */
+/*          Deserializers                                                                       */
+/*          Test Cases                                                                          */
+/* ============================================================================================ */
+use std::borrow::Cow;
+use std::fmt;
+use http::request::Request;
+use serde::de::{self, Deserialize, DeserializeSeed, IntoDeserializer, MapAccess, SeqAccess, Visitor};
+use crate::request::query_iter_decoded;
+
+/* ============================================================================================ */
+/*     Query Deserialization                                                                    */
+/* ============================================================================================ */
+
+/// Deserializes the query string of the request into a type that implements serde's Deserialize.
+/// This function is only available with the serde feature enabled.
+/// 
+/// The keys and values are decoded in the same way as query_iter_decoded. A key that appears
+/// more than once can be deserialized into a sequence such as a Vec, otherwise the first value
+/// is used. A missing key can be deserialized into an Option, or into a sequence marked with
+/// #[serde(default)]. Booleans accept true, false, 1, 0, on, off, yes and no, and a key with an
+/// empty value is true. If a value fails to parse the error names the key that failed.
+/// # Example
+/// ```
+/// use http::request::Builder;
+/// use http_tools::request::query_deserialize;
+/// use serde::Deserialize;
+/// 
+/// #[derive(Deserialize)]
+/// struct Search {
+///     page : u32,
+///     limit : Option<u32>,
+///     #[serde(default)]
+///     tag : Vec<String>,
+///     sort : String,
+/// }
+/// 
+/// let request = Builder::new()
+///                 .uri("https://www.rust-lang.org/?page=2&tag=a&tag=b&sort=new+first")
+///                 .body(()).unwrap();
+/// 
+/// let search : Search = query_deserialize(&request).unwrap();
+/// assert_eq!(search.page, 2);
+/// assert_eq!(search.limit, None);
+/// assert_eq!(search.tag, vec!["a", "b"]);
+/// assert_eq!(search.sort, "new first");
+/// 
+/// let request = Builder::new()
+///                 .uri("https://www.rust-lang.org/?page=two&sort=new")
+///                 .body(()).unwrap();
+/// 
+/// let error = query_deserialize::<Search, _>(&request).err().unwrap();
+/// assert_eq!(error.key(), Some("page"));
+/// ```
+pub fn query_deserialize<'a, T, R>(request : &'a Request<R>) -> Result<T, QueryError> where T : Deserialize<'a> {
+    // group the values by key, keeping the keys in the order they first appear
+    let mut groups : Vec<(Cow<'a, str>, Vec<Cow<'a, str>>)> = Vec::new();
+    for (key, value) in query_iter_decoded(request) {
+        match groups.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, values)) => values.push(value),
+            None => groups.push((key, vec![value])),
+        }
+    }
+    T::deserialize(QueryDeserializer { groups : groups.into_iter() })
+}
+
+/* ============================================================================================ */
+/*     Query Error                                                                              */
+/* ============================================================================================ */
+
+/// The error returned by query_deserialize, it records the key whose value failed to deserialize
+/// when there is one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryError {
+    key : Option<String>,
+    message : String,
+}
+
+impl QueryError {
+    /// Returns the query key whose value failed to deserialize, this is None for errors that
+    /// aren't caused by a single value such as a missing field.
+    pub fn key(&self) -> Option<&str> {
+        self.key.as_deref()
+    }
+    /// Returns the message describing the error
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+    // Records the key that caused the error if one hasn't been recorded already
+    fn with_key(mut self, key : &str) -> Self {
+        if self.key.is_none() {
+            self.key = Some(key.to_string());
+        }
+        self
+    }
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        match &self.key {
+            Some(key) => write!(f, "failed to deserialize query key {:?}: {}", key, self.message),
+            None => write!(f, "failed to deserialize query: {}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+impl de::Error for QueryError {
+    fn custom<T : fmt::Display>(message : T) -> Self {
+        QueryError { key : None, message : message.to_string() }
+    }
+}
+
+/* ============================================================================================ */
+/*     Deserializers                                                                            */
+/* ============================================================================================ */
+
+// Deserializes the whole query as a map from keys to values
+struct QueryDeserializer<'a> {
+    groups : std::vec::IntoIter<(Cow<'a, str>, Vec<Cow<'a, str>>)>,
+}
+
+// Walks through the grouped keys and values as a map
+struct QueryMap<'a> {
+    groups : std::vec::IntoIter<(Cow<'a, str>, Vec<Cow<'a, str>>)>,
+    current : Option<(Cow<'a, str>, Vec<Cow<'a, str>>)>,
+}
+
+// Deserializes all of the values of a single key
+struct ValuesDeserializer<'a> {
+    values : Vec<Cow<'a, str>>,
+}
+
+// Deserializes a single decoded string
+struct ValueDeserializer<'a> {
+    value : Cow<'a, str>,
+}
+
+impl<'de> de::Deserializer<'de> for QueryDeserializer<'de> {
+    type Error = QueryError;
+    fn deserialize_any<V : Visitor<'de>>(self, visitor : V) -> Result<V::Value, QueryError> {
+        visitor.visit_map(QueryMap { groups : self.groups, current : None })
+    }
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+impl<'de> MapAccess<'de> for QueryMap<'de> {
+    type Error = QueryError;
+    fn next_key_seed<K : DeserializeSeed<'de>>(&mut self, seed : K) -> Result<Option<K::Value>, QueryError> {
+        match self.groups.next() {
+            Some((key, values)) => {
+                let result = seed.deserialize(ValueDeserializer { value : key.clone() });
+                self.current = Some((key, values));
+                result.map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+    fn next_value_seed<V : DeserializeSeed<'de>>(&mut self, seed : V) -> Result<V::Value, QueryError> {
+        let (key, values) = self.current.take().ok_or_else(|| de::Error::custom("value requested before key"))?;
+        seed.deserialize(ValuesDeserializer { values }).map_err(|err| err.with_key(&key))
+    }
+}
+
+// Forwards the deserialize methods to the first value of a key
+macro_rules! forward_to_first {
+    ($($method:ident)*) => {
+        $(
+            fn $method<V : Visitor<'de>>(self, visitor : V) -> Result<V::Value, QueryError> {
+                self.first()?.$method(visitor)
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for ValuesDeserializer<'de> {
+    type Error = QueryError;
+    // anything that isn't a sequence uses the first value
+    fn deserialize_any<V : Visitor<'de>>(self, visitor : V) -> Result<V::Value, QueryError> {
+        match self.values.into_iter().next() {
+            Some(value) => ValueDeserializer { value }.deserialize_any(visitor),
+            None => visitor.visit_none(),
+        }
+    }
+    fn deserialize_option<V : Visitor<'de>>(self, visitor : V) -> Result<V::Value, QueryError> {
+        visitor.visit_some(self)
+    }
+    fn deserialize_seq<V : Visitor<'de>>(self, visitor : V) -> Result<V::Value, QueryError> {
+        visitor.visit_seq(ValuesSeq { values : self.values.into_iter() })
+    }
+    fn deserialize_newtype_struct<V : Visitor<'de>>(self, _name : &'static str, visitor : V) -> Result<V::Value, QueryError> {
+        visitor.visit_newtype_struct(self)
+    }
+    // the remaining types are forwarded to the first value so that it can parse them
+    fn deserialize_enum<V : Visitor<'de>>(self, name : &'static str, variants : &'static [&'static str], visitor : V) -> Result<V::Value, QueryError> {
+        self.first()?.deserialize_enum(name, variants, visitor)
+    }
+    forward_to_first! {
+        deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64 deserialize_i128
+        deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64 deserialize_u128 
+        deserialize_f32 deserialize_f64 deserialize_char deserialize_unit
+    }
+    serde::forward_to_deserialize_any! {
+        str string bytes byte_buf unit_struct tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+impl<'a> ValuesDeserializer<'a> {
+    fn first(self) -> Result<ValueDeserializer<'a>, QueryError> {
+        match self.values.into_iter().next() {
+            Some(value) => Ok(ValueDeserializer { value }),
+            None => Err(de::Error::custom("missing value")),
+        }
+    }
+}
+
+// Walks through the values of a key as a sequence
+struct ValuesSeq<'a> {
+    values : std::vec::IntoIter<Cow<'a, str>>,
+}
+
+impl<'de> SeqAccess<'de> for ValuesSeq<'de> {
+    type Error = QueryError;
+    fn next_element_seed<T : DeserializeSeed<'de>>(&mut self, seed : T) -> Result<Option<T::Value>, QueryError> {
+        match self.values.next() {
+            Some(value) => seed.deserialize(ValueDeserializer { value }).map(Some),
+            None => Ok(None),
+        }
+    }
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.values.len())
+    }
+}
+
+// Parses the value with FromStr and passes it to the visitor, or reports an error
+macro_rules! deserialize_parse {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V : Visitor<'de>>(self, visitor : V) -> Result<V::Value, QueryError> {
+                match self.value.parse() {
+                    Ok(value) => visitor.$visit(value),
+                    Err(err) => Err(de::Error::custom(format_args!("{:?} {}", self.value, err))),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for ValueDeserializer<'de> {
+    type Error = QueryError;
+    // values that haven't been decoded can be borrowed from the request
+    fn deserialize_any<V : Visitor<'de>>(self, visitor : V) -> Result<V::Value, QueryError> {
+        match self.value {
+            Cow::Borrowed(value) => visitor.visit_borrowed_str(value),
+            Cow::Owned(value) => visitor.visit_string(value),
+        }
+    }
+    fn deserialize_bool<V : Visitor<'de>>(self, visitor : V) -> Result<V::Value, QueryError> {
+        match &*self.value {
+            "" | "true" | "1" | "on" | "yes" => visitor.visit_bool(true),
+            "false" | "0" | "off" | "no" => visitor.visit_bool(false),
+            other => Err(de::Error::custom(format_args!("{:?} is not a boolean", other))),
+        }
+    }
+    fn deserialize_option<V : Visitor<'de>>(self, visitor : V) -> Result<V::Value, QueryError> {
+        visitor.visit_some(self)
+    }
+    fn deserialize_unit<V : Visitor<'de>>(self, visitor : V) -> Result<V::Value, QueryError> {
+        visitor.visit_unit()
+    }
+    fn deserialize_newtype_struct<V : Visitor<'de>>(self, _name : &'static str, visitor : V) -> Result<V::Value, QueryError> {
+        visitor.visit_newtype_struct(self)
+    }
+    // a single value can be deserialized into a sequence of one
+    fn deserialize_seq<V : Visitor<'de>>(self, visitor : V) -> Result<V::Value, QueryError> {
+        visitor.visit_seq(ValuesSeq { values : vec![self.value].into_iter() })
+    }
+    // only unit variants can be named in a query value
+    fn deserialize_enum<V : Visitor<'de>>(self, _name : &'static str, _variants : &'static [&'static str], visitor : V) -> Result<V::Value, QueryError> {
+        visitor.visit_enum(self.value.into_deserializer())
+    }
+    deserialize_parse! {
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char,
+    }
+    serde::forward_to_deserialize_any! {
+        str string bytes byte_buf unit_struct tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+/* ============================================================================================ */
+/*     Test Cases                                                                               */
+/* ============================================================================================ */
+#[cfg(test)]
+#[derive(Debug, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Sort {
+    New,
+    Top,
+}
+
+#[cfg(test)]
+#[derive(Debug, PartialEq, serde::Deserialize)]
+struct Search<'a> {
+    page : u32,
+    limit : Option<u8>,
+    #[serde(default)]
+    tag : Vec<String>,
+    sort : Sort,
+    debug : Option<bool>,
+    #[serde(borrow)]
+    name : Option<Cow<'a, str>>,
+}
+
+#[test]
+fn test_query_deserialize() {
+    use http::request::Builder;
+    let request = Builder::new().uri("/?page=2&tag=a&tag=b%20c&sort=top&debug=on&name=rust&extra=1").body(()).unwrap();
+    let search : Search = query_deserialize(&request).unwrap();
+    assert_eq!(search, Search { 
+        page : 2, 
+        limit : None, 
+        tag : vec!["a".to_string(), "b c".to_string()], 
+        sort : Sort::Top, 
+        debug : Some(true),
+        name : Some(Cow::Borrowed("rust")),
+    });
+    let request = Builder::new().uri("/?page=1&sort=new&limit=10&name=John+Doe").body(()).unwrap();
+    let search : Search = query_deserialize(&request).unwrap();
+    assert_eq!(search.limit, Some(10));
+    assert!(search.tag.is_empty());
+    assert_eq!(search.name, Some(Cow::Owned("John Doe".to_string())));
+}
+
+#[test]
+fn test_query_deserialize_errors() {
+    use http::request::Builder;
+    let request = Builder::new().uri("/?page=2&sort=new&limit=1000").body(()).unwrap();
+    let error = query_deserialize::<Search, _>(&request).unwrap_err();
+    assert_eq!(error.key(), Some("limit"));
+    let request = Builder::new().uri("/?page=2&sort=old").body(()).unwrap();
+    let error = query_deserialize::<Search, _>(&request).unwrap_err();
+    assert_eq!(error.key(), Some("sort"));
+    let request = Builder::new().uri("/?page=2&sort=new&debug=maybe").body(()).unwrap();
+    let error = query_deserialize::<Search, _>(&request).unwrap_err();
+    assert_eq!(error.key(), Some("debug"));
+    let request = Builder::new().uri("/?sort=new").body(()).unwrap();
+    let error = query_deserialize::<Search, _>(&request).unwrap_err();
+    assert_eq!(error.key(), None);
+    assert_eq!(error.to_string(), "failed to deserialize query: missing field `page`");
+}
@@ -0,0 +1,397 @@
+// MIT License
+// 
+// Copyright (c) 2019 Jonathon Davis
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software. 
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+/* ============================================================================================ */
+/*     Document Structure                                                                       */
+/*          Filter Rejection                                                                    */
+/*          Diagnose Trait                                                                      */
+/*          impl Diagnose for Result<Request, FilterRejection>                                  */
+/*          Test Cases                                                                          */
+/* ============================================================================================ */
+use std::fmt;
+use http::request::Request;
+use http::response::{Builder, Response};
+use http::header::{HeaderValue, WWW_AUTHENTICATE};
+use http::method::Method;
+use http::status::StatusCode;
+use crate::request::{query_iter, Filter};
+use crate::request::path::{try_match_path, Constraint};
+
+/* ============================================================================================ */
+/*     Filter Rejection                                                                         */
+/* ============================================================================================ */
+
+/// A FilterRejection records which filter a request failed, along with what the filter expected
+/// and what the request actually had. All of the values are borrowed from either the arguments
+/// of the filter or from the request, so no allocation is made.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterRejection<'a> {
+    /// The path didn't match the pattern given to filter_path or filter_path_with. Expected is
+    /// the segment of the pattern that didn't match, or None if the path was longer than the pattern,
+    /// and actual is the segment of the path, or None if the path was shorter than the pattern.
+    Path { pattern : &'a str, expected : Option<&'a str>, actual : Option<&'a str> },
+    /// The path didn't begin with the pattern given to filter_path_prefix, the segments are 
+    /// reported in the same way as Path
+    PathPrefix { pattern : &'a str, expected : Option<&'a str>, actual : Option<&'a str> },
+    /// The method of the request wasn't the expected method
+    Method { expected : &'a str, actual : &'a Method },
+    /// The header wasn't found with the expected value, actual is the first value of the
+    /// header or None if the header was missing
+    Header { key : &'a str, expected : &'a str, actual : Option<&'a HeaderValue> },
+    /// The query wasn't found with the expected value, actual is the first value of the
+    /// query key or None if the key was missing
+    Query { key : &'a str, expected : &'a str, actual : Option<&'a str> },
+    /// The scheme of the request wasn't the expected scheme, actual is None if the uri didn't
+    /// have a scheme
+    Scheme { expected : &'a str, actual : Option<&'a str> },
+    /// The function given to filter_custom returned false
+    Custom,
+}
+
+impl<'a> fmt::Display for FilterRejection<'a> {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        // writes out an optional value, or the text nothing if it is None
+        fn or_nothing<T : fmt::Debug>(value : &Option<T>) -> String {
+            match value {
+                Some(value) => format!("{:?}", value),
+                None => "nothing".to_string(),
+            }
+        }
+        match self {
+            FilterRejection::Path { pattern, expected, actual } => 
+                write!(f, "path did not match {:?}, expected {} but found {}", pattern, or_nothing(expected), or_nothing(actual)),
+            FilterRejection::PathPrefix { pattern, expected, actual } => 
+                write!(f, "path did not begin with {:?}, expected {} but found {}", pattern, or_nothing(expected), or_nothing(actual)),
+            FilterRejection::Method { expected, actual } => 
+                write!(f, "method did not match, expected {:?} but found {:?}", expected, actual.as_str()),
+            FilterRejection::Header { key, expected, actual } => 
+                write!(f, "header {:?} did not match, expected {:?} but found {}", key, expected, or_nothing(actual)),
+            FilterRejection::Query { key, expected, actual } => 
+                write!(f, "query {:?} did not match, expected {:?} but found {}", key, expected, or_nothing(actual)),
+            FilterRejection::Scheme { expected, actual } => 
+                write!(f, "scheme did not match, expected {:?} but found {}", expected, or_nothing(actual)),
+            FilterRejection::Custom => write!(f, "custom filter returned false"),
+        }
+    }
+}
+
+impl<'a> std::error::Error for FilterRejection<'a> {}
+
+impl<'a> FilterRejection<'a> {
+    /// Returns the status code that best describes why the request was rejected
+    /// 
+    /// | Rejection                      | Status                     |
+    /// |--------------------------------|----------------------------|
+    /// | Path, PathPrefix               | 404 Not Found              |
+    /// | Method                         | 405 Method Not Allowed     |
+    /// | Header authorization           | 401 Unauthorized           |
+    /// | Header proxy-authorization     | 407 Proxy Auth Required    |
+    /// | Header accept                  | 406 Not Acceptable         |
+    /// | Header content-type            | 415 Unsupported Media Type |
+    /// | Any other Header, Query, Scheme or Custom | 400 Bad Request |
+    pub fn status(&self) -> StatusCode {
+        match self {
+            FilterRejection::Path { .. } | FilterRejection::PathPrefix { .. } => StatusCode::NOT_FOUND,
+            FilterRejection::Method { .. } => StatusCode::METHOD_NOT_ALLOWED,
+            FilterRejection::Header { key, .. } if key.eq_ignore_ascii_case("authorization") => StatusCode::UNAUTHORIZED,
+            FilterRejection::Header { key, .. } if key.eq_ignore_ascii_case("proxy-authorization") => StatusCode::PROXY_AUTHENTICATION_REQUIRED,
+            FilterRejection::Header { key, .. } if key.eq_ignore_ascii_case("accept") => StatusCode::NOT_ACCEPTABLE,
+            FilterRejection::Header { key, .. } if key.eq_ignore_ascii_case("content-type") => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            _ => StatusCode::BAD_REQUEST,
+        }
+    }
+    /// Returns a response Builder with the status from FilterRejection::status. An authorization 
+    /// rejection includes a WWW-Authenticate header when the expected value begins with an 
+    /// authentication scheme.
+    /// 
+    /// A Method rejection doesn't include an Allow header, since a 405 has to list every method 
+    /// the resource supports and a single chain of filters only knows the one it expected. Whether
+    /// a chain results in a 404 or a 405 also depends on whether filter_path or filter_method runs
+    /// first. Use Router::resolve to answer a request with the correct 404 or 405 and Allow header.
+    /// # Example
+    /// ```
+    /// use http::request::Builder;
+    /// use http_tools::request::{Diagnose, Extension};
+    /// 
+    /// let request = Builder::new()
+    ///                 .uri("https://www.rust-lang.org/")
+    ///                 .header("content-type", "text/plain")
+    ///                 .body(()).unwrap();
+    /// 
+    /// let response = request.diagnose()
+    ///     .filter_header("content-type", "application/json")
+    ///     .map(|_request| http::Response::builder().body(()).unwrap())
+    ///     .unwrap_or_else(|rejection| rejection.response().body(()).unwrap());
+    /// assert_eq!(response.status(), 415);
+    /// ```
+    pub fn response(&self) -> Builder {
+        let mut builder = Builder::new();
+        builder.status(self.status());
+        match self {
+            FilterRejection::Header { expected, .. } if self.status() == StatusCode::UNAUTHORIZED => {
+                // the scheme is the first word of the credentials such as Bearer or Basic
+                let scheme = expected.split_whitespace().next().unwrap_or("");
+                if !scheme.is_empty() && scheme != "{}" {
+                    builder.header(WWW_AUTHENTICATE, scheme);
+                }
+            }
+            _ => (),
+        }
+        builder
+    }
+}
+
+// A rejection can be turned directly into a response with an empty body
+impl<'a, B : Default> From<FilterRejection<'a>> for Response<B> {
+    fn from(rejection : FilterRejection<'a>) -> Self {
+        rejection.response()
+            .body(B::default())
+            .unwrap_or_else(|_| {
+                // the expected value may not be a valid header, in which case fall back to the status
+                let mut response = Response::new(B::default());
+                *response.status_mut() = rejection.status();
+                response
+            })
+    }
+}
+
+/* ============================================================================================ */
+/*     Diagnose Trait                                                                           */
+/* ============================================================================================ */
+
+/// The Diagnose trait mirrors the Filter trait, except that it works on a 
+/// Result<&Request, FilterRejection> instead of an Option<&Request>. When a filter fails the
+/// result records which filter failed and why, rather than just returning None. The first
+/// filter to fail is the one that is recorded, any filters after it are skipped.
+/// 
+/// Diagnose is meant for debugging and error reporting, the Filter trait remains the fastest
+/// way to filter requests. The filters make the same decisions as their Filter counterparts,
+/// but the method and header values are given as &str so that they can be recorded.
+/// # Example
+/// ```
+/// # use http::request::Builder;
+/// use http_tools::request::{Diagnose, Extension, FilterRejection};
+/// # let request = Builder::new()
+/// #                .uri("https://www.rust-lang.org/item/rust")
+/// #                .method("POST")
+/// #                .body(()).unwrap();
+/// 
+/// // given an http::request::Request
+/// let result = request
+///     // Creates a Result<&Request, FilterRejection> instead of an Option<&Request>
+///     .diagnose()
+///     .filter_path("/item/{}")
+///     .filter_method("GET")
+///     .filter_scheme("https");
+/// 
+/// // the method filter failed, and the scheme filter was skipped
+/// match result {
+///     Err(FilterRejection::Method { expected, actual }) => {
+///         assert_eq!(expected, "GET");
+///         assert_eq!(actual, "POST");
+///     }
+///     _ => panic!("the method filter should have failed"),
+/// }
+/// ```
+pub trait Diagnose<'a, R> {
+    /// Diagnosing version of Filter::filter_header
+    fn filter_header(self, key : &'a str, value : &'a str) -> Self;
+    /// Diagnosing version of Filter::filter_path
+    fn filter_path(self, pattern : &'a str) -> Self;
+    /// Diagnosing version of Filter::filter_path_with
+    fn filter_path_with(self, pattern : &'a str, constraints : &[Constraint]) -> Self;
+    /// Diagnosing version of Filter::filter_path_prefix
+    fn filter_path_prefix(self, pattern : &'a str) -> Self;
+    /// Diagnosing version of Filter::filter_method
+    fn filter_method(self, method : &'a str) -> Self;
+    /// Diagnosing version of Filter::filter_query
+    fn filter_query(self, key : &'a str, value : &'a str) -> Self;
+    /// Diagnosing version of Filter::filter_scheme
+    fn filter_scheme(self, scheme : &'a str) -> Self;
+    /// Diagnosing version of Filter::filter_custom
+    fn filter_custom(self, func : fn(&Request<R>) -> bool) -> Self;
+}
+
+/* ============================================================================================ */
+/*     impl Diagnose for Result<Request, FilterRejection>                                       */
+/* ============================================================================================ */
+
+// The Diagnose implementation defers to the Filter implementation for Option<&Request> to decide
+// if the request passes, so that both traits always agree. Only when a request fails is the
+// FilterRejection built. The exception is the path filters, which need to know which segment
+// didn't match and so call path::try_match_path directly.
+impl<'a, R> Diagnose<'a, R> for Result<&'a Request<R>, FilterRejection<'a>> {
+    fn filter_header(self, key : &'a str, value : &'a str) -> Self {
+        let request = self?;
+        if Some(request).filter_header(key, value).is_some() {
+            return Ok(request);
+        }
+        // a wildcard key doesn't refer to a single header, so there is no actual value
+        let actual = if key == "{}" { None } else { request.headers().get(key) };
+        Err(FilterRejection::Header { key, expected : value, actual })
+    }
+    fn filter_path(self, pattern : &'a str) -> Self {
+        let request = self?;
+        match try_match_path(pattern, request.uri().path(), false, &[]) {
+            Ok(_) => Ok(request),
+            Err(mismatch) => Err(FilterRejection::Path { pattern, expected : mismatch.expected, actual : mismatch.actual }),
+        }
+    }
+    fn filter_path_with(self, pattern : &'a str, constraints : &[Constraint]) -> Self {
+        let request = self?;
+        match try_match_path(pattern, request.uri().path(), false, constraints) {
+            Ok(_) => Ok(request),
+            Err(mismatch) => Err(FilterRejection::Path { pattern, expected : mismatch.expected, actual : mismatch.actual }),
+        }
+    }
+    fn filter_path_prefix(self, pattern : &'a str) -> Self {
+        let request = self?;
+        match try_match_path(pattern, request.uri().path(), true, &[]) {
+            Ok(_) => Ok(request),
+            Err(mismatch) => Err(FilterRejection::PathPrefix { pattern, expected : mismatch.expected, actual : mismatch.actual }),
+        }
+    }
+    fn filter_method(self, method : &'a str) -> Self {
+        let request = self?;
+        if Some(request).filter_method(method).is_some() {
+            return Ok(request);
+        }
+        Err(FilterRejection::Method { expected : method, actual : request.method() })
+    }
+    fn filter_query(self, key : &'a str, value : &'a str) -> Self {
+        let request = self?;
+        if Some(request).filter_query(key, value).is_some() {
+            return Ok(request);
+        }
+        // a wildcard key doesn't refer to a single query, so there is no actual value
+        let actual = if key == "{}" { 
+            None 
+        } else { 
+            query_iter(request).find(|(q_key, _)| *q_key == key).map(|(_, q_value)| q_value)
+        };
+        Err(FilterRejection::Query { key, expected : value, actual })
+    }
+    fn filter_scheme(self, scheme : &'a str) -> Self {
+        let request = self?;
+        if Some(request).filter_scheme(scheme).is_some() {
+            return Ok(request);
+        }
+        Err(FilterRejection::Scheme { expected : scheme, actual : request.uri().scheme_str() })
+    }
+    fn filter_custom(self, func : fn(&Request<R>) -> bool) -> Self {
+        let request = self?;
+        if func(request) {
+            return Ok(request);
+        }
+        Err(FilterRejection::Custom)
+    }
+}
+
+/* ============================================================================================ */
+/*     Test Cases                                                                               */
+/* ============================================================================================ */
+#[test]
+fn test_diagnose_pass() {
+    use http::request::Builder;
+    use crate::request::Extension;
+    let request = Builder::new()
+        .uri("https://www.rust-lang.org/item/rust?cool=rust")
+        .method("POST")
+        .header("key", "value")
+        .body(()).unwrap();
+    let result = request.diagnose()
+        .filter_path("/item/{}")
+        .filter_path_prefix("/item")
+        .filter_method("POST")
+        .filter_header("key", "value")
+        .filter_query("cool", "rust")
+        .filter_scheme("https")
+        .filter_custom(|_| true);
+    assert!(result.is_ok());
+}
+
+#[test]
+fn test_diagnose_rejections() {
+    use http::request::Builder;
+    use crate::request::Extension;
+    let request = Builder::new()
+        .uri("https://www.rust-lang.org/item/rust?cool=rust")
+        .method("POST")
+        .header("key", "value")
+        .body(()).unwrap();
+    let result = request.diagnose().filter_path("/item/{}/more");
+    assert_eq!(result.err(), Some(FilterRejection::Path { pattern : "/item/{}/more", expected : Some("more"), actual : None }));
+    let result = request.diagnose().filter_path_prefix("/other");
+    assert_eq!(result.err(), Some(FilterRejection::PathPrefix { pattern : "/other", expected : Some("other"), actual : Some("item") }));
+    let result = request.diagnose().filter_header("key", "other");
+    assert_eq!(result.err(), Some(FilterRejection::Header { key : "key", expected : "other", actual : request.headers().get("key") }));
+    let result = request.diagnose().filter_header("missing", "{}");
+    assert_eq!(result.err(), Some(FilterRejection::Header { key : "missing", expected : "{}", actual : None }));
+    let result = request.diagnose().filter_query("cool", "go");
+    assert_eq!(result.err(), Some(FilterRejection::Query { key : "cool", expected : "go", actual : Some("rust") }));
+    let result = request.diagnose().filter_scheme("http");
+    assert_eq!(result.err(), Some(FilterRejection::Scheme { expected : "http", actual : Some("https") }));
+    let result = request.diagnose().filter_custom(|_| false);
+    assert_eq!(result.err(), Some(FilterRejection::Custom));
+}
+
+#[test]
+fn test_rejection_response() {
+    use http::request::Builder;
+    use crate::request::Extension;
+    let request = Builder::new().uri("https://www.rust-lang.org/item").method("GET").body(()).unwrap();
+    let status = |result : Result<_, FilterRejection>| result.err().unwrap().status();
+    assert_eq!(status(request.diagnose().filter_path("/other")), StatusCode::NOT_FOUND);
+    assert_eq!(status(request.diagnose().filter_path_prefix("/other")), StatusCode::NOT_FOUND);
+    assert_eq!(status(request.diagnose().filter_method("POST")), StatusCode::METHOD_NOT_ALLOWED);
+    assert_eq!(status(request.diagnose().filter_header("Authorization", "{}")), StatusCode::UNAUTHORIZED);
+    assert_eq!(status(request.diagnose().filter_header("proxy-authorization", "{}")), StatusCode::PROXY_AUTHENTICATION_REQUIRED);
+    assert_eq!(status(request.diagnose().filter_header("accept", "text/html")), StatusCode::NOT_ACCEPTABLE);
+    assert_eq!(status(request.diagnose().filter_header("content-type", "text/html")), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    assert_eq!(status(request.diagnose().filter_header("x-custom", "{}")), StatusCode::BAD_REQUEST);
+    assert_eq!(status(request.diagnose().filter_query("key", "{}")), StatusCode::BAD_REQUEST);
+    assert_eq!(status(request.diagnose().filter_scheme("http")), StatusCode::BAD_REQUEST);
+    assert_eq!(status(request.diagnose().filter_custom(|_| false)), StatusCode::BAD_REQUEST);
+
+    let response : Response<()> = request.diagnose().filter_method("POST").err().unwrap().into();
+    assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+    assert!(response.headers().get(http::header::ALLOW).is_none());
+    let response : Response<()> = request.diagnose().filter_header("authorization", "Bearer token").err().unwrap().into();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(response.headers()[WWW_AUTHENTICATE], "Bearer");
+    let response : Response<()> = request.diagnose().filter_header("authorization", "{}").err().unwrap().into();
+    assert!(response.headers().get(WWW_AUTHENTICATE).is_none());
+}
+
+#[test]
+fn test_diagnose_first_failure() {
+    use http::request::Builder;
+    use crate::request::Extension;
+    let request = Builder::new().uri("https://www.rust-lang.org/").method("GET").body(()).unwrap();
+    let result = request.diagnose()
+        .filter_scheme("http")
+        .filter_method("POST");
+    let rejection = result.err().unwrap();
+    assert_eq!(rejection, FilterRejection::Scheme { expected : "http", actual : Some("https") });
+    let message = rejection.to_string();
+    assert_eq!(message, "scheme did not match, expected \"http\" but found \"https\"");
+}
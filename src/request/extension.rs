@@ -19,7 +19,11 @@
 // LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
+use std::borrow::Cow;
 use http::request::Request;
+use http::header::{HeaderValue, COOKIE};
+use crate::encoding::query_decode;
+use crate::request::{FilterRejection, Query};
 
 /// The Extension trait provides additional methods to the Http Request type
 pub trait Extension {
@@ -28,6 +32,12 @@ pub trait Extension {
     /// through a filter function it will return Some if the inner 
     /// Request passed the filter, or None if the inner Request failed the filter. 
     fn filter(&self) -> Option<&Self>;
+    /// Creates a Result<&Request, FilterRejection> that can be filtered on using the
+    /// Diagnose trait. This works like filter, except that when the inner Request fails
+    /// a filter the result records which filter failed and why.
+    fn diagnose(&self) -> Result<&Self, FilterRejection<'_>> {
+        Ok(self)
+    }
 }
 
 impl<R> Extension for Request<R> {
@@ -35,10 +45,11 @@ impl<R> Extension for Request<R> {
     fn filter(&self) -> Option<&Self> {
         Some(self)
     }
-
 }
 
-/// Returns an iterator over a query string
+/// Returns an iterator over a query string. Each pair is split on the first '=', so everything
+/// after it is part of the value, and a flag without an '=' such as 'debug' has an empty value.
+/// Query can be used for lookups by key, and to tell flags apart from empty values.
 /// 
 /// # Example
 /// ```
@@ -47,7 +58,7 @@ impl<R> Extension for Request<R> {
 ///
 /// // given an  http request
 /// let request = Builder::new()
-///                 .uri("https://www.rust-lang.org/?one=two&three=four")
+///                 .uri("https://www.rust-lang.org/?one=two&three=four&five")
 ///                 .body(()).unwrap();
 /// 
 /// // use the http_tools function to create an iterator
@@ -58,15 +69,94 @@ impl<R> Extension for Request<R> {
 /// // will print out 
 /// // one two
 /// // three four
+/// // five
 /// ```
 pub fn query_iter<'a, R>(request : &'a Request<R>) -> impl 'a + Iterator<Item=(&'a str, &'a str)> {
-    request.uri().query()
-        .unwrap_or("")
-        .split("&")
-        .map(|q| {
-            let mut q = q.split('=').fuse();
-            (q.next(), q.next())
+    Query::new(request).iter()
+}
+
+/// Returns an iterator over a query string where the keys and values have been decoded. Both 
+/// percent encodings such as %20 and the '+' used for spaces are decoded. Keys and values that
+/// didn't need decoding are borrowed from the request, so no allocation is made for them.
+/// 
+/// # Example
+/// ```
+/// use http::request::Builder;
+/// use http_tools::request::query_iter_decoded;
+///
+/// // given an  http request
+/// let request = Builder::new()
+///                 .uri("https://www.rust-lang.org/?name=John+Doe&city=New%20York")
+///                 .body(()).unwrap();
+/// 
+/// // use the http_tools function to create an iterator
+/// for (key, value) in query_iter_decoded(&request){
+///     println!("{} {}", key, value)
+/// }
+/// 
+/// // will print out 
+/// // name John Doe
+/// // city New York
+/// ```
+pub fn query_iter_decoded<'a, R>(request : &'a Request<R>) -> impl 'a + Iterator<Item=(Cow<'a, str>, Cow<'a, str>)> {
+    query_iter(request).map(|(key, value)| (query_decode(key), query_decode(value)))
+}
+
+/// Returns an iterator over the names and values of the cookies sent with a request. The cookies
+/// of every Cookie header are returned in order, and the names and values are borrowed from the
+/// headers. Values surrounded by quotes have the quotes removed, but are otherwise left as they
+/// were sent, and pairs without an '=' are skipped.
+/// 
+/// # Example
+/// ```
+/// use http::request::Builder;
+/// use http_tools::request::cookie_iter;
+///
+/// // given an  http request
+/// let request = Builder::new()
+///                 .uri("https://www.rust-lang.org/")
+///                 .header("cookie", "session=abc123; theme=dark")
+///                 .header("cookie", "lang=\"en\"")
+///                 .body(()).unwrap();
+/// 
+/// // use the http_tools function to create an iterator
+/// for (name, value) in cookie_iter(&request){
+///     println!("{} {}", name, value)
+/// }
+/// 
+/// // will print out 
+/// // session abc123
+/// // theme dark
+/// // lang en
+/// ```
+pub fn cookie_iter<R>(request : &Request<R>) -> impl '_ + Iterator<Item=(&str, &str)> {
+    request.headers()
+        .get_all(COOKIE)
+        .into_iter()
+        .filter_map(|value : &HeaderValue| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|pair| {
+            let equals = pair.find('=')?;
+            let value = pair[equals + 1..].trim();
+            // a cookie value may be surrounded by quotes, which aren't part of the value
+            let value = match value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+                true => &value[1..value.len() - 1],
+                false => value,
+            };
+            Some((pair[..equals].trim(), value))
         })
-        .filter(|(key, value)| key.is_some() && value.is_some())
-        .map(|(key, value)| (key.unwrap(), value.unwrap())) 
+        .filter(|(name, _)| !name.is_empty())
+}
+
+#[test]
+fn test_cookie_iter() {
+    use http::request::Builder;
+    let request = Builder::new()
+        .header("cookie", "a=1; b = 2 ;;c=; flag; =orphan; d=\"quoted\"; e=x=y")
+        .header("cookie", "f=6")
+        .body(()).unwrap();
+    let cookies = cookie_iter(&request).collect::<Vec<_>>();
+    assert_eq!(cookies, vec![("a", "1"), ("b", "2"), ("c", ""), ("d", "quoted"), ("e", "x=y"), ("f", "6")]);
+    let request = Builder::new().body(()).unwrap();
+    assert_eq!(cookie_iter(&request).count(), 0);
 }
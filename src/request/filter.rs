@@ -27,9 +27,12 @@
 /*          Test Cases                                                                          */
 /* ============================================================================================ */
 use http::request::Request;
-use http::header::HeaderValue;
+use http::header::{HeaderValue, CONTENT_LENGTH, TRANSFER_ENCODING};
+use crate::header::{content_length, content_type, list_contains};
+use crate::predicate::Predicate;
 use http::method::Method;
-use crate::request::query_iter;
+use crate::request::{accept, cookie_iter, query_iter, query_iter_decoded, RequestMatcher};
+use crate::request::path::{match_path, match_path_decoded, Constraint};
 
 /* ============================================================================================ */
 /*     Filter Trait                                                                             */
@@ -145,7 +148,8 @@ pub trait Filter<'a, R> {
     /// Checks to see if the request has the specified key and value. The wildcard '{}'
     /// pattern can be used in either the key or the value string. The function returns
     /// Some(request) if the header with the key and value are found or None if they are
-    /// absent. 
+    /// absent. When a header is repeated every value is checked, the value is compared with
+    /// the whole header value, see filter_header_contains for matching list elements.
     /// # Example
     /// ```
     /// use http::request::Builder;
@@ -166,9 +170,57 @@ pub trait Filter<'a, R> {
     /// assert!(filter.is_some());
     /// ```
     fn filter_header<T>(self, key : &str, value : T) -> Self where T : PartialEq<HeaderValue> + PartialEq<&'static str>;
+    /// Checks to see if the request has a list header where one of the comma separated elements
+    /// is the value, such as Upgrade in 'Connection: keep-alive, Upgrade'. Every value of a
+    /// repeated header is checked, and the elements are trimmed before they are compared. The
+    /// wildcard '{}' pattern can be used in either the key or the value string.
+    /// # Example
+    /// ```
+    /// use http::request::Builder;
+    /// use http_tools::request::{Extension, Filter};
+    /// 
+    /// // Request Builder found in http crate
+    /// let request = Builder::new()
+    ///                 .uri("https://www.rust-lang.org/")
+    ///                 .header("connection", "keep-alive, Upgrade")
+    ///                 .body(()).unwrap();
+    /// 
+    /// // matches as Upgrade is an element of the list
+    /// let filter = request.filter().filter_header_contains("connection", "Upgrade");
+    /// assert!(filter.is_some());
+    /// 
+    /// // doesn't match as the comparison is case sensitive
+    /// let filter = request.filter().filter_header_contains("connection", "upgrade");
+    /// assert!(filter.is_none());
+    /// ```
+    fn filter_header_contains(self, key : &str, value : &str) -> Self;
+    /// Works the same as filter_header_contains except that the elements are compared without
+    /// regard to case, which is how tokens such as those in the Connection, Upgrade and
+    /// Transfer-Encoding headers are compared.
+    /// # Example
+    /// ```
+    /// use http::request::Builder;
+    /// use http_tools::request::{Extension, Filter};
+    /// 
+    /// // Request Builder found in http crate
+    /// let request = Builder::new()
+    ///                 .uri("https://www.rust-lang.org/")
+    ///                 .header("connection", "keep-alive, Upgrade")
+    ///                 .body(()).unwrap();
+    /// 
+    /// let filter = request.filter().filter_header_contains_ignore_case("connection", "upgrade");
+    /// assert!(filter.is_some());
+    /// ```
+    fn filter_header_contains_ignore_case(self, key : &str, value : &str) -> Self;
     /// Checks to see if the requests path matches the specified pattern. The wildcard '{}'
     /// pattern can be used can be used to match any text between foward slashes
-    /// so '/{}' will match '/any' but not '/any/more'
+    /// so '/{}' will match '/any' but not '/any/more'. A wildcard can also be given a name 
+    /// such as '/{id}', the name has no effect on matching but can be used to look up the 
+    /// matched segment with path_captures. A wildcard can be constrained to a type such as
+    /// '/{id:u64}', in which case the filter will return None if the segment doesn't satisfy it.
+    /// The last segment of the pattern can be a catch all such as '/static/{*rest}' which will 
+    /// match zero or more trailing segments.
+    /// A pattern can contain at most MAX_CAPTURES wildcards, a pattern with more never matches.
     /// # Example
     /// ```
     /// use http::request::Builder;
@@ -186,10 +238,63 @@ pub trait Filter<'a, R> {
     /// // this will match because the wildcard '{}' will match var
     /// let filter = request.filter().filter_path("/{}/static");
     /// assert!(filter.is_some());
+    /// 
+    /// // this will match because the named wildcard '{dir}' will match var
+    /// let filter = request.filter().filter_path("/{dir}/static");
+    /// assert!(filter.is_some());
+    /// 
+    /// // this will NOT match because var doesn't satisfy the u64 constraint
+    /// let filter = request.filter().filter_path("/{dir:u64}/static");
+    /// assert!(filter.is_none());
+    /// 
+    /// // this will match because the catch all '{*rest}' will match static
+    /// let filter = request.filter().filter_path("/var/{*rest}");
+    /// assert!(filter.is_some());
     /// ```
     fn filter_path(self, pattern : &str) -> Self;
+    /// The same as filter_path, except that the given custom constraints can be used in the pattern
+    /// alongside the built in constraints. See path_captures for the list of built in constraints.
+    /// # Example
+    /// ```
+    /// use http::request::Builder;
+    /// use http_tools::request::{Extension, Filter};
+    /// 
+    /// // Request Builder found in http crate
+    /// let request = Builder::new()
+    ///                     .uri("https://www.rust-lang.org/blog/hello-world")
+    ///                     .body(()).unwrap();
+    /// 
+    /// // this will match because hello-world only contains lowercase letters and hyphens
+    /// let filter = request.filter().filter_path_with("/blog/{:slug}", &[
+    ///     ("slug", |s| s.bytes().all(|b| b.is_ascii_lowercase() || b == b'-')),
+    /// ]);
+    /// assert!(filter.is_some());
+    /// ```
+    fn filter_path_with(self, pattern : &str, constraints : &[Constraint]) -> Self;
+    /// The same as filter_path, except that each segment of the path is percent decoded before it
+    /// is compared to the pattern. The path is split on forward slashes before it is decoded, so 
+    /// an encoded slash %2F is part of a segment rather than a seperator.
+    /// # Example
+    /// ```
+    /// use http::request::Builder;
+    /// use http_tools::request::{Extension, Filter};
+    /// 
+    /// // Request Builder found in http crate
+    /// let request = Builder::new()
+    ///                     .uri("https://www.rust-lang.org/files/my%20file")
+    ///                     .body(()).unwrap();
+    /// 
+    /// // this will match because %20 is decoded into a space
+    /// let filter = request.filter().filter_path_decoded("/files/my file");
+    /// assert!(filter.is_some());
+    /// 
+    /// // this will NOT match because filter_path doesn't decode the path
+    /// let filter = request.filter().filter_path("/files/my file");
+    /// assert!(filter.is_none());
+    /// ```
+    fn filter_path_decoded(self, pattern : &str) -> Self;
     /// Checks to see if the requests path begins with the specified pattern. The wildcard '{}'
-    /// pattern can be used to match any text between foward slashes.
+    /// or named wildcard '{name}' pattern can be used to match any text between foward slashes.
     /// # Example
     /// ```
     /// use http::request::Builder;
@@ -238,7 +343,10 @@ pub trait Filter<'a, R> {
     /// ```
     fn filter_method<T>(self, method : T) -> Self where T : PartialEq<Method>;
     /// Checks to see if the uri contains a query with the given key and value. The wildcard '{}'
-    /// pattern can be used to match any key or value.
+    /// pattern can be used to match any key or value. A flag without a value such as '?debug'
+    /// has an empty value, so filter_query("debug", "{}") checks that the key is present
+    /// and filter_query("debug", "") checks that the key has an empty value, which matches both
+    /// '?debug' and '?debug='. Use Query::pairs to tell the two apart.
     /// # Example
     /// ```
     /// use http::request::Builder;
@@ -260,6 +368,10 @@ pub trait Filter<'a, R> {
     /// let filter = request.filter().filter_query("{}", "go");
     /// assert!(filter.is_some());
     /// 
+    /// // this will NOT match because the key missing isn't present
+    /// let filter = request.filter().filter_query("missing", "{}");
+    /// assert!(filter.is_none());
+    /// 
     /// // this will NOT match because filter_query does not decode it's arguments
     /// let filter = request.filter().filter_query("also cool", "go");
     /// assert!(filter.is_none());
@@ -268,6 +380,52 @@ pub trait Filter<'a, R> {
     /// assert!(filter.is_some());
     /// ```
     fn filter_query(self, key : &str, value : &str) -> Self;
+    /// Checks to see if the request has a cookie with the given name and value. The wildcard '{}'
+    /// pattern can be used to match any name or value. The cookies of every Cookie header are
+    /// checked, and a value surrounded by quotes is compared without its quotes.
+    /// # Example
+    /// ```
+    /// use http::request::Builder;
+    /// use http_tools::request::{Extension, Filter};
+    /// // Request Builder found in http crate
+    /// let request = Builder::new()
+    ///                     .uri("https://www.rust-lang.org/")
+    ///                     .header("cookie", "session=abc123; theme=dark")
+    ///                     .body(()).unwrap();
+    /// 
+    /// // this will match because the cookie theme has the value dark
+    /// let filter = request.filter().filter_cookie("theme", "dark");
+    /// assert!(filter.is_some());
+    /// 
+    /// // this will match because the cookie session exists
+    /// let filter = request.filter().filter_cookie("session", "{}");
+    /// assert!(filter.is_some());
+    /// 
+    /// // this will NOT match because there isn't a lang cookie
+    /// let filter = request.filter().filter_cookie("lang", "{}");
+    /// assert!(filter.is_none());
+    /// ```
+    fn filter_cookie(self, name : &str, value : &str) -> Self;
+    /// The same as filter_query, except that the keys and values of the query are decoded before
+    /// they are compared. Both percent encodings such as %20 and the '+' used for spaces are decoded.
+    /// # Example
+    /// ```
+    /// use http::request::Builder;
+    /// use http_tools::request::{Extension, Filter};
+    /// // Request Builder found in http crate
+    /// let request = Builder::new()
+    ///                     .uri("https://www.rust-lang.org/?name=John%20Doe&also+cool=go")
+    ///                     .body(()).unwrap();
+    /// 
+    /// // this will match as John%20Doe is decoded into John Doe
+    /// let filter = request.filter().filter_query_decoded("name", "John Doe");
+    /// assert!(filter.is_some());
+    /// 
+    /// // this will match as also+cool is decoded into also cool
+    /// let filter = request.filter().filter_query_decoded("also cool", "{}");
+    /// assert!(filter.is_some());
+    /// ```
+    fn filter_query_decoded(self, key : &str, value : &str) -> Self;
     /// Checks to see if the request has given scheme
     /// # Example
     /// ```
@@ -286,6 +444,77 @@ pub trait Filter<'a, R> {
     /// assert!(filter.is_none());
     /// ```
     fn filter_scheme(self, scheme : &str) -> Self;
+    /// Checks to see if the Content-Type of the request is the given media type, the parameters
+    /// of the Content-Type such as the charset are ignored and the media type is compared without
+    /// regard to case. The media type can use a '*' wildcard, such as text/*, and constants for
+    /// common media types can be found in http_tools::header::mime.
+    /// # Example
+    /// ```
+    /// use http::request::Builder;
+    /// use http_tools::request::{Extension, Filter};
+    /// use http_tools::header::mime;
+    /// // Request Builder found in http crate
+    /// let request = Builder::new()
+    ///                     .uri("https://www.rust-lang.org/")
+    ///                     .header("content-type", "application/json; charset=utf-8")
+    ///                     .body(()).unwrap();
+    /// 
+    /// // this will match as the charset is ignored
+    /// let filter = request.filter().filter_content_type(mime::JSON);
+    /// assert!(filter.is_some());
+    /// // this will also match
+    /// let filter = request.filter().filter_content_type("application/*");
+    /// assert!(filter.is_some());
+    /// // this will not 
+    /// let filter = request.filter().filter_content_type(mime::HTML);
+    /// assert!(filter.is_none());
+    /// ```
+    fn filter_content_type(self, media_type : &str) -> Self;
+    /// Checks that the body of the request is at most max bytes long according to the 
+    /// Content-Length header. A request without a Content-Length or Transfer-Encoding header
+    /// has no body and passes, while a request with a Transfer-Encoding but no Content-Length,
+    /// or with an invalid Content-Length, has an unknown length and fails. The filter only looks
+    /// at the headers, so the body itself still needs to be limited as it is read.
+    /// # Example
+    /// ```
+    /// use http::request::Builder;
+    /// use http_tools::request::{Extension, Filter};
+    /// // Request Builder found in http crate
+    /// let request = Builder::new()
+    ///                     .uri("https://www.rust-lang.org/")
+    ///                     .header("content-length", "2048")
+    ///                     .body(()).unwrap();
+    /// 
+    /// // this will match
+    /// let filter = request.filter().filter_content_length_max(4096);
+    /// assert!(filter.is_some());
+    /// // this will not as the body is too long
+    /// let filter = request.filter().filter_content_length_max(1024);
+    /// assert!(filter.is_none());
+    /// ```
+    fn filter_content_length_max(self, max : u64) -> Self;
+    /// Checks to see if the request accepts the given media type according to its Accept header,
+    /// the media type is accepted if the most specific media range that matches it has a quality
+    /// above zero. A request without an Accept header accepts every media type. To pick between
+    /// several media types use request::accept instead.
+    /// # Example
+    /// ```
+    /// use http::request::Builder;
+    /// use http_tools::request::{Extension, Filter};
+    /// // Request Builder found in http crate
+    /// let request = Builder::new()
+    ///                     .uri("https://www.rust-lang.org/")
+    ///                     .header("accept", "text/html, application/*;q=0.5, application/xml;q=0")
+    ///                     .body(()).unwrap();
+    /// 
+    /// // this will match
+    /// let filter = request.filter().filter_accepts("application/json");
+    /// assert!(filter.is_some());
+    /// // this will not as xml has a quality of zero
+    /// let filter = request.filter().filter_accepts("application/xml");
+    /// assert!(filter.is_none());
+    /// ```
+    fn filter_accepts(self, media_type : &str) -> Self;
     /// filter_custom allows for a custom function filter. The filter will be given a &Request and
     /// will output a bool. if the bool is true, then function returns Some, if it is false then the
     /// function will return None
@@ -304,6 +533,148 @@ pub trait Filter<'a, R> {
     /// assert!(filter.is_some());
     /// ```
     fn filter_custom(self, func : fn(&Request<R>) -> bool) -> Self;
+    /// filter_fn works the same as filter_custom, except that it accepts any closure rather than
+    /// only a fn, so the closure can capture state such as configuration. To reuse the same closure
+    /// across many filters use filter_predicate.
+    /// # Example
+    /// ```
+    /// use http::request::Builder;
+    /// use http_tools::request::{Extension, Filter};
+    /// // Request Builder found in http crate
+    /// let request = Builder::new()
+    ///                     .uri("https://www.rust-lang.org/")
+    ///                     .header("x-tenant", "acme")
+    ///                     .body(()).unwrap();
+    /// 
+    /// // an allow list that was loaded at startup
+    /// let allowed = vec!["acme", "globex"];
+    /// 
+    /// // this will match as the tenant is in the allow list
+    /// let filter = request.filter().filter_fn(|request| {
+    ///     request.headers().get("x-tenant").map_or(false, |tenant| allowed.iter().any(|allowed| tenant == allowed))
+    /// });
+    /// assert!(filter.is_some());
+    /// ```
+    fn filter_fn<F>(self, func : F) -> Self where F : FnOnce(&Request<R>) -> bool;
+    /// filter_predicate tests the request with a Predicate, which is a shared function that can
+    /// capture state. The Predicate can be built once and cloned into as many routes as needed.
+    /// # Example
+    /// ```
+    /// use http::request::{Builder, Request};
+    /// use http_tools::request::{Extension, Filter};
+    /// use http_tools::predicate::Predicate;
+    /// // Request Builder found in http crate
+    /// let request = Builder::new()
+    ///                     .uri("https://www.rust-lang.org/")
+    ///                     .header("x-tenant", "acme")
+    ///                     .body(()).unwrap();
+    /// 
+    /// // an allow list that was loaded at startup, shared by every route
+    /// let allowed = vec!["acme", "globex"];
+    /// let tenant = Predicate::new(move |request : &Request<()>| {
+    ///     request.headers().get("x-tenant").map_or(false, |tenant| allowed.iter().any(|allowed| tenant == allowed))
+    /// });
+    /// 
+    /// // this will match as the tenant is in the allow list
+    /// let filter = request.filter().filter_predicate(&tenant);
+    /// assert!(filter.is_some());
+    /// ```
+    fn filter_predicate(self, predicate : &Predicate<Request<R>>) -> Self;
+    /// filter_matcher passes the request through every condition of a RequestMatcher, which holds
+    /// the conditions of a chain of filters as a value that can be stored and shared.
+    /// # Example
+    /// ```
+    /// use http::request::Builder;
+    /// use http_tools::request::{Extension, Filter, RequestMatcher};
+    /// // Request Builder found in http crate
+    /// let request = Builder::new()
+    ///                     .uri("https://www.rust-lang.org/item/rust")
+    ///                     .method("GET")
+    ///                     .body(()).unwrap();
+    /// 
+    /// let matcher = RequestMatcher::new().with_path("/item/{}").with_method("GET");
+    /// 
+    /// // this will match as the request has the path and method of the matcher
+    /// let filter = request.filter().filter_matcher(&matcher);
+    /// assert!(filter.is_some());
+    /// ```
+    fn filter_matcher(self, matcher : &RequestMatcher) -> Self;
+    /// filter_any passes the request if either of the filters pass, which allows for alternatives
+    /// within a chain. Each filter is given the current Option<&Request> and can chain any number 
+    /// of filters, the second filter is only run if the first one fails.
+    /// # Example
+    /// ```
+    /// use http::request::Builder;
+    /// use http_tools::request::{Extension, Filter};
+    /// // Request Builder found in http crate
+    /// let request = Builder::new()
+    ///                     .uri("https://www.rust-lang.org/b")
+    ///                     .method("HEAD")
+    ///                     .body(()).unwrap();
+    /// 
+    /// // this will match as the second filter passes
+    /// let filter = request.filter().filter_any(|r| r.filter_method("GET"), |r| r.filter_method("HEAD"));
+    /// assert!(filter.is_some());
+    /// ```
+    fn filter_any<A, B>(self, first : A, second : B) -> Self where Self : Sized, A : FnOnce(Self) -> Self, B : FnOnce(Self) -> Self;
+    /// filter_any_of passes the request if any of the filters pass, the filters are run in order
+    /// until one of them passes. Closures that don't capture anything can be given in the slice.
+    /// # Example
+    /// ```
+    /// use http::request::Builder;
+    /// use http_tools::request::{Extension, Filter};
+    /// // Request Builder found in http crate
+    /// let request = Builder::new()
+    ///                     .uri("https://www.rust-lang.org/b")
+    ///                     .method("HEAD")
+    ///                     .body(()).unwrap();
+    /// 
+    /// // this will match as the second filter passes
+    /// let filter = request.filter().filter_any_of(&[|r| r.filter_method("GET"), |r| r.filter_method("HEAD"), |r| r.filter_path("/a")]);
+    /// assert!(filter.is_some());
+    /// ```
+    fn filter_any_of(self, filters : &[fn(Self) -> Self]) -> Self where Self : Sized;
+    /// filter_not passes the request if the filter fails, and fails the request if the filter passes.
+    /// A request that has already failed a filter earlier in the chain stays failed.
+    /// # Example
+    /// ```
+    /// use http::request::Builder;
+    /// use http_tools::request::{Extension, Filter};
+    /// // Request Builder found in http crate
+    /// let request = Builder::new()
+    ///                     .uri("https://www.rust-lang.org/b")
+    ///                     .method("HEAD")
+    ///                     .body(()).unwrap();
+    /// 
+    /// // this will match as the filter fails
+    /// let filter = request.filter().filter_not(|r| r.filter_method("GET"));
+    /// assert!(filter.is_some());
+    /// // this will not
+    /// let filter = request.filter().filter_not(|r| r.filter_method("HEAD"));
+    /// assert!(filter.is_none());
+    /// ```
+    fn filter_not<F>(self, filter : F) -> Self where Self : Sized, F : FnOnce(Self) -> Self;
+    /// filter_group runs the filter as a single step of the chain, which allows a group of filters 
+    /// to be written once as a function and reused across chains, or to be combined with filter_not.
+    /// # Example
+    /// ```
+    /// use http::request::Builder;
+    /// use http_tools::request::{Extension, Filter};
+    /// // Request Builder found in http crate
+    /// let request = Builder::new()
+    ///                     .uri("https://www.rust-lang.org/b")
+    ///                     .method("HEAD")
+    ///                     .body(()).unwrap();
+    /// 
+    /// // a group of filters written as a function
+    /// fn is_head<'a, R>(filter : Option<&'a http::Request<R>>) -> Option<&'a http::Request<R>> {
+    ///     filter.filter_not(|r| r.filter_method("GET")).filter_any(|r| r.filter_method("HEAD"), |r| r.filter_path("/a"))
+    /// }
+    /// 
+    /// let filter = request.filter().filter_group(is_head);
+    /// assert!(filter.is_some());
+    /// ```
+    fn filter_group<F>(self, filter : F) -> Self where Self : Sized, F : FnOnce(Self) -> Self;
 }
 
 /* ============================================================================================ */
@@ -337,6 +708,37 @@ impl<'a, R> Filter<'a, R> for Option<&Request<R>>{
         // If the filter broke out, or self was None then return None
         None
     }
+    // The filter_cookie function for Option<&Request> works the same as filter_query, except 
+    // that it uses request::cookie_iter to iterate through the cookies
+    fn filter_cookie(self, name : &str, value : &str) -> Self {
+        if let Some(request) = self {
+            for (c_name, c_value) in cookie_iter(request) {
+                if (name == "{}" || name == c_name) && (value == "{}" || value == c_value) {
+                    return Some(request);
+                }
+            }
+        }
+        // If the filter broke out, or self was None then return None
+        None
+    }
+    // The filter_query_decoded function for Option<&Request> works the same as filter_query,
+    // except that it uses request::query_iter_decoded to iterate through the query arguments
+    fn filter_query_decoded(self, key : &str, value : &str) -> Self {
+        // since the filter functions can return none, we can't perform any work (and shouldn't)
+        // if a previous filter invalidated the Request
+        if let Some(request) = self {
+            // iterate through the decoded querys
+            for (q_key, q_value) in query_iter_decoded(request) {
+                // if the key == q_key or {} and the value == q_value or {} then the pattern
+                // matches and we can return the refrence
+                if (key == "{}" || key == q_key) && (value == "{}" || value == q_value) {
+                    return Some(request);
+                }
+            }
+        }
+        // If the filter broke out, or self was None then return None
+        None
+    }
     // The filter_header function for Option<&Request> first checks to see that the value
     // of self is Some. Then it checks the key, if the key is a wild card then the values
     // will need to be iterated through to check to see if they match, if the key is not
@@ -351,7 +753,7 @@ impl<'a, R> Filter<'a, R> for Option<&Request<R>>{
                 let map = request.headers();
                 // If the value is {} and there are entries in the header map
                 // the return Some request as any value would match
-                if value == "{}" && map.len() > 0 {
+                if value == "{}" && !map.is_empty() {
                     return Some(request);
                 }
                 // Iterate through the different values to see if any values
@@ -363,100 +765,95 @@ impl<'a, R> Filter<'a, R> for Option<&Request<R>>{
                     }
                 }
             } else {
-                // Get the key and check if it's value is equal to the inputed value
+                // Get the values of the key and check if any are equal to the inputed value
                 // otherwise fall through to the end and return None
-                match request.headers().get(key) {
-                    Some(v) if value == *v || value == "{}" => return Some(request),
-                    _ => (),
+                let mut values = request.headers().get_all(key).iter().peekable();
+                if values.peek().is_some() && value == "{}" {
+                    return Some(request);
+                }
+                if values.any(|v| value == *v) {
+                    return Some(request);
                 }
             }
         }
         // If the filter broke out, or self was None then return None
         None
     }
+    // The filter_header_contains function for Option<&Request> splits every value of the header
+    // into its list elements and checks if any of them are equal to the inputed value
+    fn filter_header_contains(self, key : &str, value : &str) -> Self {
+        if let Some(request) = self {
+            if list_contains(request.headers(), key, value, false) {
+                return self;
+            }
+        }
+        None
+    }
+    // The filter_header_contains_ignore_case function works the same as filter_header_contains
+    // except that the elements are compared without regard to case
+    fn filter_header_contains_ignore_case(self, key : &str, value : &str) -> Self {
+        if let Some(request) = self {
+            if list_contains(request.headers(), key, value, true) {
+                return self;
+            }
+        }
+        None
+    }
     // The filter_path function for Option<&Request> first checks to see that the value of
     // self is Some, then it checks to see if the path of the request matches the pattern.
-    // if they aren't an exact match then the filter checks to see if there are any variable
-    // wildcards {}.
+    // The path and pattern are walked one segment at a time by path::match_path, where
+    // a wildcard {} or {name} segment in the pattern will match any segment in the path.
     fn filter_path(self, pattern : &str) -> Self {
         // since the filter functions can return none, we can't perform any work (and shouldn't)
         // if a previous filter invalidated the Request
         if let Some(request) = self {
-            // get the path from the uri
-            let path = request.uri().path();
-            // check to see if the path and pattern equal eachother
-            if path == pattern {
+            // walk the path and the pattern segment by segment
+            if match_path(pattern, request.uri().path(), false, &[]).is_some() {
+                return Some(request);
+            }
+        }
+        // If the filter broke out, or self was None then return None
+        None
+    }
+    // The filter_path_with function for Option<&Request> is the same as filter_path except that
+    // the custom constraints are passed along to path::match_path.
+    fn filter_path_with(self, pattern : &str, constraints : &[Constraint]) -> Self {
+        // since the filter functions can return none, we can't perform any work (and shouldn't)
+        // if a previous filter invalidated the Request
+        if let Some(request) = self {
+            // walk the path and the pattern segment by segment
+            if match_path(pattern, request.uri().path(), false, constraints).is_some() {
+                return Some(request);
+            }
+        }
+        // If the filter broke out, or self was None then return None
+        None
+    }
+    // The filter_path_decoded function for Option<&Request> is the same as filter_path except that
+    // the path is matched with path::match_path_decoded which decodes each segment.
+    fn filter_path_decoded(self, pattern : &str) -> Self {
+        // since the filter functions can return none, we can't perform any work (and shouldn't)
+        // if a previous filter invalidated the Request
+        if let Some(request) = self {
+            // walk the path and the pattern segment by segment
+            if match_path_decoded(pattern, request.uri().path(), false, &[]) {
                 return Some(request);
-            } else {
-                // create two iterators split on the forward slash for both
-                // the pattern given as an argument and the actual path of 
-                // the request being filtered
-                let mut split_pattern = pattern.split('/');
-                let mut split_path = path.split('/');
-                loop {
-                    // call next on each of the iterators
-                    let pattern_item = split_pattern.next();
-                    let path_item = split_path.next();
-                    match (pattern_item, path_item) {
-                        // if they both have a result check to see if the pattern is a wildcard or they equal eachother
-                        (Some(pattern), Some(path)) if pattern != "{}" && pattern != path => return None,
-                        // if the pattern or path end before one another then they are not the same length, thus not equal
-                        (None, Some(_)) | (Some(_), None) => return None,
-                        // if both the pattern and path end at the same time then they have been equal up to this point
-                        // and are assumed to be equal
-                        (None, None) => return Some(request),
-                        // if both the pattern and path are equal or the pattern is {} continue with the loop
-                        _ => ()
-                    }
-                }
             }
         }
         // If the filter broke out, or self was None then return None
         None
     }
     // The filter_path_prefix function for Option<&Request> first checks to see that the value of
-    // self is Some, then it checks to see if the path of the request matches the pattern.
-    // if they aren't an exact match then the filter checks to see if there are any variable
-    // wildcards {}.
+    // self is Some, then it checks to see if the path of the request begins with the pattern.
+    // This uses the same segment walking as filter_path, except that the path is allowed to
+    // continue past the end of the pattern.
     fn filter_path_prefix(self, pattern : &str) -> Self {
         // since the filter functions can return none, we can't perform any work (and shouldn't)
         // if a previous filter invalidated the Request
         if let Some(request) = self {
-            // get the path from the uri
-            let path = request.uri().path();
-            // check to see if the path and pattern equal eachother
-            if path == pattern {
+            // walk the path and the pattern segment by segment
+            if match_path(pattern, request.uri().path(), true, &[]).is_some() {
                 return Some(request);
-            } else {
-                // create two iterators split on the forward slash for both
-                // the pattern given as an argument and the actual path of 
-                // the request being filtered
-                let mut split_pattern = pattern.split('/');
-                let mut split_path = path.split('/');
-                loop {
-                    // call next on each of the iterators
-                    let pattern_item = split_pattern.next();
-                    let path_item = split_path.next();
-                    match (pattern_item, path_item) {
-                        // since we are using the split operator if the pattern ends with a /
-                        // then there will be a lingering "". check to make sure it is a lingering
-                        // "" and not one in the middle of the pattern
-                        (Some(pattern), Some(path)) if pattern == "" && pattern != path => {
-                            match split_pattern.next() {
-                                Some(_) => return None,
-                                None => return Some(request),
-                            }
-                        }
-                        // check to see if the pattern and path differ
-                        (Some(pattern), Some(path)) if pattern != "{}" && pattern != path => return None,
-                        // if the path ends before the pattern then it is not a prefix
-                        (Some(_), None) => return None,
-                        // if the pattern ends before the path or they both end at the same time then its a prefix
-                        (None, Some(_)) | (None, None) => return Some(request),
-                        // if they equal eachother continue through the loop
-                        _ => ()
-                    }
-                }
             }
         }
         // If the filter broke out, or self was None then return None
@@ -489,6 +886,40 @@ impl<'a, R> Filter<'a, R> for Option<&Request<R>>{
         // If the filter broke out, or self was None then return None
         None
     }
+    // The filter_content_type function for Option<&Request> parses the Content-Type header
+    // and compares its type and subtype, a missing or invalid Content-Type fails the filter
+    fn filter_content_type(self, media_type : &str) -> Self {
+        if let Some(request) = self {
+            match content_type(request.headers()) {
+                Some(content_type) if content_type.is(media_type) => return self,
+                _ => (),
+            }
+        }
+        None
+    }
+    // The filter_content_length_max function for Option<&Request> checks the Content-Length,
+    // when it is missing the body is empty unless a Transfer-Encoding was given
+    fn filter_content_length_max(self, max : u64) -> Self {
+        if let Some(request) = self {
+            let headers = request.headers();
+            match content_length(headers) {
+                Some(length) if length <= max => return self,
+                None if !headers.contains_key(CONTENT_LENGTH) && !headers.contains_key(TRANSFER_ENCODING) => return self,
+                _ => (),
+            }
+        }
+        None
+    }
+    // The filter_accepts function for Option<&Request> finds the quality of the media type
+    // using the Accept headers, the request passes if the quality isn't zero
+    fn filter_accepts(self, media_type : &str) -> Self {
+        if let Some(request) = self {
+            if accept(request).accepts(media_type) {
+                return self;
+            }
+        }
+        None
+    }
     // The filter_scheme function for Option<&Request> first checks to see that the value of
     // self is Some, then checks to see if the request scheme is equal to the inputed scheme.
     fn filter_custom(self, func : fn(&Request<R>) -> bool) -> Self {
@@ -500,6 +931,57 @@ impl<'a, R> Filter<'a, R> for Option<&Request<R>>{
         }
        None
     }
+    // The filter_fn function for Option<&Request> works the same as filter_custom
+    fn filter_fn<F>(self, func : F) -> Self where F : FnOnce(&Request<R>) -> bool {
+        if let Some(request) = self {
+            if func(request) {
+                return self;
+            }
+        }
+        None
+    }
+    // The filter_predicate function for Option<&Request> tests the request with the predicate
+    fn filter_predicate(self, predicate : &Predicate<Request<R>>) -> Self {
+        if let Some(request) = self {
+            if predicate.test(request) {
+                return self;
+            }
+        }
+        None
+    }
+    // The filter_matcher function for Option<&Request> passes the request through the filters
+    // of each condition of the matcher
+    fn filter_matcher(self, matcher : &RequestMatcher) -> Self {
+        matcher.apply(self)
+    }
+    // The filter_any function for Option<&Request> runs the first filter, and only runs the
+    // second filter if the first one failed. The request is passed along as it was before the
+    // filters, as the alternatives only decide if it passes.
+    fn filter_any<A, B>(self, first : A, second : B) -> Self where A : FnOnce(Self) -> Self, B : FnOnce(Self) -> Self {
+        if self.is_some() && (first(self).is_some() || second(self).is_some()) {
+            return self;
+        }
+        None
+    }
+    // The filter_any_of function for Option<&Request> runs each filter until one passes
+    fn filter_any_of(self, filters : &[fn(Self) -> Self]) -> Self {
+        if self.is_some() && filters.iter().any(|filter| filter(self).is_some()) {
+            return self;
+        }
+        None
+    }
+    // The filter_not function for Option<&Request> inverts the result of the filter, but only
+    // if the request hasn't already failed
+    fn filter_not<F>(self, filter : F) -> Self where F : FnOnce(Self) -> Self {
+        if self.is_some() && filter(self).is_none() {
+            return self;
+        }
+        None
+    }
+    // The filter_group function for Option<&Request> simply runs the filter
+    fn filter_group<F>(self, filter : F) -> Self where F : FnOnce(Self) -> Self {
+        filter(self)
+    }
 }
 
 /* ============================================================================================ */
@@ -532,6 +1014,51 @@ fn test_var_route() {
     assert!(filter.is_some());
 }
 
+#[test]
+fn test_named_var_route() {
+    use http::request::Builder;
+    use crate::request::Extension;
+    let request = Builder::new().uri("https://www.rust-lang.org/users/42/posts/7").body(()).unwrap();
+    let filter = request.filter().filter_path("/users/{id}/posts/{post_id}");
+    assert!(filter.is_some());
+    let filter = request.filter().filter_path_prefix("/users/{id}");
+    assert!(filter.is_some());
+    let filter = request.filter().filter_path("/users/{id}");
+    assert!(filter.is_none());
+}
+
+#[test]
+fn test_constrained_route() {
+    use http::request::Builder;
+    use crate::request::Extension;
+    let request = Builder::new().uri("https://www.rust-lang.org/item/42").body(()).unwrap();
+    let filter = request.filter().filter_path("/item/{id:u64}");
+    assert!(filter.is_some());
+    let filter = request.filter().filter_path("/item/{:alpha}");
+    assert!(filter.is_none());
+    let filter = request.filter().filter_path_prefix("/{:u64}");
+    assert!(filter.is_none());
+    let filter = request.filter().filter_path_with("/item/{:even}", &[("even", |s| s.ends_with('2'))]);
+    assert!(filter.is_some());
+    let filter = request.filter().filter_path_with("/item/{:odd}", &[("odd", |s| s.ends_with('1'))]);
+    assert!(filter.is_none());
+}
+
+#[test]
+fn test_catch_all_route() {
+    use http::request::Builder;
+    use crate::request::Extension;
+    let request = Builder::new().uri("https://www.rust-lang.org/static/css/site.css").body(()).unwrap();
+    let filter = request.filter().filter_path("/static/{*rest}");
+    assert!(filter.is_some());
+    let filter = request.filter().filter_path("/{*rest}");
+    assert!(filter.is_some());
+    let filter = request.filter().filter_path("/static/css/site.css/{*rest}");
+    assert!(filter.is_some());
+    let filter = request.filter().filter_path("/public/{*rest}");
+    assert!(filter.is_none());
+}
+
 #[test]
 fn test_partial_route() {
     use http::request::Builder;
@@ -623,6 +1150,51 @@ fn test_query() {
     assert!(filter.is_none());
 }
 
+#[test]
+fn test_query_flags() {
+    use http::request::Builder;
+    use crate::request::Extension;
+    let request = Builder::new().uri("https://www.rust-lang.org/?debug&eq=a=b&tag=a&tag=b").body(()).unwrap();
+    let filter = request.filter().filter_query("debug", "{}");
+    assert!(filter.is_some());
+    let filter = request.filter().filter_query("debug", "");
+    assert!(filter.is_some());
+    let filter = request.filter().filter_query("eq", "a=b");
+    assert!(filter.is_some());
+    let filter = request.filter().filter_query("tag", "b");
+    assert!(filter.is_some());
+    let filter = request.filter().filter_query("verbose", "{}");
+    assert!(filter.is_none());
+}
+
+#[test]
+fn test_query_decoded() {
+    use http::request::Builder;
+    use crate::request::Extension;
+    let request = Builder::new().uri("https://www.rust-lang.org/?name=John%20Doe&also+cool=go").body(()).unwrap();
+    let filter = request.filter().filter_query_decoded("name", "John Doe");
+    assert!(filter.is_some());
+    let filter = request.filter().filter_query_decoded("also cool", "go");
+    assert!(filter.is_some());
+    let filter = request.filter().filter_query_decoded("{}", "John Doe");
+    assert!(filter.is_some());
+    let filter = request.filter().filter_query_decoded("name", "John%20Doe");
+    assert!(filter.is_none());
+}
+
+#[test]
+fn test_path_decoded() {
+    use http::request::Builder;
+    use crate::request::Extension;
+    let request = Builder::new().uri("https://www.rust-lang.org/files/my%20file").body(()).unwrap();
+    let filter = request.filter().filter_path_decoded("/files/my file");
+    assert!(filter.is_some());
+    let filter = request.filter().filter_path_decoded("/files/{}");
+    assert!(filter.is_some());
+    let filter = request.filter().filter_path_decoded("/files/my%20file");
+    assert!(filter.is_none());
+}
+
 #[test]
 fn test_method() {
     use http::request::Builder;
@@ -668,4 +1240,155 @@ fn test_multiple_filters(){
     assert!(filter.is_some());
     let filter = request.filter().filter_scheme("http").filter_method("get");
     assert!(filter.is_none());
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_content_type() {
+    use http::request::Builder;
+    use crate::request::Extension;
+    use crate::header::mime;
+    let request = Builder::new().header("content-type", "Text/HTML;charset=utf-8").body(()).unwrap();
+    assert!(request.filter().filter_content_type(mime::HTML).is_some());
+    assert!(request.filter().filter_content_type("text/*").is_some());
+    assert!(request.filter().filter_content_type(mime::ANY).is_some());
+    assert!(request.filter().filter_content_type(mime::TEXT).is_none());
+    let request = Builder::new().body(()).unwrap();
+    assert!(request.filter().filter_content_type(mime::ANY).is_none());
+}
+
+#[test]
+fn test_content_length_max() {
+    use http::request::Builder;
+    use crate::request::Extension;
+    let request = Builder::new().header("content-length", "10").body(()).unwrap();
+    assert!(request.filter().filter_content_length_max(10).is_some());
+    assert!(request.filter().filter_content_length_max(9).is_none());
+    let request = Builder::new().body(()).unwrap();
+    assert!(request.filter().filter_content_length_max(0).is_some());
+    let request = Builder::new().header("transfer-encoding", "chunked").body(()).unwrap();
+    assert!(request.filter().filter_content_length_max(1024).is_none());
+    let request = Builder::new().header("content-length", "ten").body(()).unwrap();
+    assert!(request.filter().filter_content_length_max(1024).is_none());
+}
+
+#[test]
+fn test_header_repeated() {
+    use http::request::Builder;
+    use crate::request::Extension;
+    let request = Builder::new().uri("https://www.rust-lang.org/")
+        .header("accept-encoding", "gzip")
+        .header("accept-encoding", "br")
+        .body(()).unwrap();
+    let filter = request.filter().filter_header("accept-encoding", "br");
+    assert!(filter.is_some());
+    let filter = request.filter().filter_header("accept-encoding", "{}");
+    assert!(filter.is_some());
+    let filter = request.filter().filter_header("accept-encoding", "deflate");
+    assert!(filter.is_none());
+}
+
+#[test]
+fn test_header_contains() {
+    use http::request::Builder;
+    use crate::request::Extension;
+    let request = Builder::new().uri("https://www.rust-lang.org/")
+        .header("connection", "keep-alive, Upgrade")
+        .header("connection", "close")
+        .body(()).unwrap();
+    let filter = request.filter().filter_header_contains("connection", "Upgrade");
+    assert!(filter.is_some());
+    let filter = request.filter().filter_header_contains("connection", "close");
+    assert!(filter.is_some());
+    let filter = request.filter().filter_header_contains("connection", "keep-alive, Upgrade");
+    assert!(filter.is_none());
+    let filter = request.filter().filter_header_contains("connection", "upgrade");
+    assert!(filter.is_none());
+    let filter = request.filter().filter_header_contains_ignore_case("connection", "UPGRADE");
+    assert!(filter.is_some());
+    let filter = request.filter().filter_header_contains_ignore_case("{}", "Close");
+    assert!(filter.is_some());
+    let filter = request.filter().filter_header_contains_ignore_case("upgrade", "{}");
+    assert!(filter.is_none());
+}
+
+#[test]
+fn test_accepts() {
+    use http::request::Builder;
+    use crate::request::Extension;
+    let request = Builder::new().header("accept", "text/*, text/plain;q=0").body(()).unwrap();
+    assert!(request.filter().filter_accepts("text/html").is_some());
+    assert!(request.filter().filter_accepts("text/plain").is_none());
+    assert!(request.filter().filter_accepts("application/json").is_none());
+    let request = Builder::new().body(()).unwrap();
+    assert!(request.filter().filter_accepts("application/json").is_some());
+}
+
+#[test]
+fn test_cookie() {
+    use http::request::Builder;
+    use crate::request::Extension;
+    let request = Builder::new()
+        .header("cookie", "session=abc123; theme=dark")
+        .header("cookie", "lang=en")
+        .body(()).unwrap();
+    let filter = request.filter().filter_cookie("theme", "dark");
+    assert!(filter.is_some());
+    let filter = request.filter().filter_cookie("lang", "en");
+    assert!(filter.is_some());
+    let filter = request.filter().filter_cookie("{}", "abc123");
+    assert!(filter.is_some());
+    let filter = request.filter().filter_cookie("theme", "light");
+    assert!(filter.is_none());
+    let filter = request.filter().filter_cookie("missing", "{}");
+    assert!(filter.is_none());
+    let request = Builder::new().body(()).unwrap();
+    let filter = request.filter().filter_cookie("{}", "{}");
+    assert!(filter.is_none());
+}
+
+#[test]
+fn test_combinators() {
+    use http::request::Builder;
+    use crate::request::Extension;
+    let request = Builder::new().uri("https://www.rust-lang.org/b").method("HEAD").body(()).unwrap();
+    let filter = request.filter().filter_any(|r| r.filter_method("GET"), |r| r.filter_method("HEAD"));
+    assert!(filter.is_some());
+    let filter = request.filter().filter_any(|r| r.filter_path("/a"), |r| r.filter_path("/c"));
+    assert!(filter.is_none());
+    let filter = request.filter().filter_any_of(&[|r| r.filter_path("/a"), |r| r.filter_path("/b")]);
+    assert!(filter.is_some());
+    let filter = request.filter().filter_any_of(&[]);
+    assert!(filter.is_none());
+    let filter = request.filter().filter_not(|r| r.filter_scheme("http"));
+    assert!(filter.is_some());
+    let filter = request.filter().filter_not(|r| r.filter_scheme("https"));
+    assert!(filter.is_none());
+    let filter = request.filter().filter_group(|r| r.filter_path("/b").filter_method("HEAD"));
+    assert!(filter.is_some());
+    // a request that already failed stays failed
+    let filter = request.filter().filter_path("/a").filter_not(|r| r.filter_method("GET"));
+    assert!(filter.is_none());
+    let filter = request.filter().filter_path("/a").filter_any(|r| r, |r| r);
+    assert!(filter.is_none());
+    // the alternatives only decide if the request passes, they don't change it
+    let filter = request.filter().filter_any(|r| r.filter_method("HEAD"), |_| None).filter_path("/b");
+    assert!(filter.is_some());
+}
+
+#[test]
+fn test_fn_and_predicate() {
+    use http::request::Builder;
+    use crate::request::Extension;
+    let request = Builder::new().uri("https://www.rust-lang.org/").header("x-tenant", "acme").body(()).unwrap();
+    let tenants = ["acme".to_string()];
+    let filter = request.filter().filter_fn(|request| request.headers().get("x-tenant").map_or(false, |t| tenants.iter().any(|a| t == a.as_str())));
+    assert!(filter.is_some());
+    let filter = request.filter().filter_fn(|_| false);
+    assert!(filter.is_none());
+    let tenant = Predicate::new(move |request : &Request<()>| request.headers().get("x-tenant").map_or(false, |t| tenants.iter().any(|a| t == a.as_str())));
+    let shared = tenant.clone();
+    assert!(request.filter().filter_predicate(&tenant).is_some());
+    assert!(request.filter().filter_predicate(&shared).is_some());
+    assert!(request.filter().filter_predicate(&tenant.not()).is_none());
+    assert!(request.filter().filter_scheme("http").filter_predicate(&tenant).is_none());
+}
@@ -0,0 +1,250 @@
+// MIT License
+// 
+// Copyright (c) 2019 Jonathon Davis
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software. 
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+/* ============================================================================================ */
+/*     Document Structure                                                                       */
+/*          RequestMatcher                                                                      */
+/*          Test Cases                                                                          */
+/* ============================================================================================ */
+use http::request::Request;
+use http::method::Method;
+use http::HttpTryFrom;
+use crate::request::{Extension, Filter};
+use crate::request::conflict::{covers, overlaps};
+use crate::request::path::pattern_error;
+
+/* ============================================================================================ */
+/*     RequestMatcher                                                                           */
+/* ============================================================================================ */
+
+/// A RequestMatcher holds the conditions of a chain of filters as a value, so that the matching 
+/// rules of a route can be stored, shared between routes and inspected. Each condition works the
+/// same as the filter of the same name: the path is matched with filter_path or filter_path_prefix,
+/// the headers with filter_header, the queries with filter_query and the scheme with filter_scheme,
+/// so the {} wildcard can be used in the same places. A request matches when it passes every
+/// condition, except for the methods, where the request only needs to have one of them. A 
+/// RequestMatcher without any conditions matches every request.
+/// 
+/// Building a RequestMatcher allocates, but matching a request does not.
+/// # Example
+/// ```
+/// use http::request::Builder;
+/// use http_tools::request::{Extension, Filter, RequestMatcher};
+/// 
+/// let matcher = RequestMatcher::new()
+///     .with_path("/item/{}")
+///     .with_method("GET")
+///     .with_method("HEAD")
+///     .with_header("accept", "{}")
+///     .with_query("cool", "rust")
+///     .with_scheme("https");
+/// 
+/// let request = Builder::new()
+///                 .uri("https://www.rust-lang.org/item/rust?cool=rust")
+///                 .header("accept", "*/*")
+///                 .body(()).unwrap();
+/// assert!(matcher.matches(&request));
+/// 
+/// // the matcher can also be used in a chain of filters
+/// assert!(request.filter().filter_matcher(&matcher).filter_header("accept", "*/*").is_some());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RequestMatcher {
+    path : Option<String>,
+    prefix : bool,
+    methods : Vec<Method>,
+    headers : Vec<(String, String)>,
+    queries : Vec<(String, String)>,
+    scheme : Option<String>,
+}
+
+impl RequestMatcher {
+    /// Creates a RequestMatcher without any conditions, which matches every request
+    pub fn new() -> Self {
+        RequestMatcher::default()
+    }
+    /// Sets the pattern that the whole path must match, replacing any previous path
+    /// # Panics
+    /// Panics if the pattern can never match a path, because a catch all isn't the last segment,
+    /// it has more than MAX_CAPTURES wildcards, or it has a constraint that isn't built in.
+    pub fn with_path(mut self, pattern : &str) -> Self {
+        if let Some(error) = pattern_error(pattern, &[]) {
+            panic!("the path {} {}", pattern, error);
+        }
+        self.path = Some(pattern.to_string());
+        self.prefix = false;
+        self
+    }
+    /// Sets the pattern that the path must begin with, replacing any previous path
+    /// # Panics
+    /// Panics for the same patterns as RequestMatcher::with_path
+    pub fn with_path_prefix(mut self, pattern : &str) -> Self {
+        if let Some(error) = pattern_error(pattern, &[]) {
+            panic!("the path {} {}", pattern, error);
+        }
+        self.path = Some(pattern.to_string());
+        self.prefix = true;
+        self
+    }
+    /// Adds a method, the request must have one of the methods that were added
+    /// # Panics
+    /// Panics if the method is not a valid http method
+    pub fn with_method<M>(mut self, method : M) -> Self where Method : HttpTryFrom<M> {
+        let method = match Method::try_from(method) {
+            Ok(method) => method,
+            Err(_) => panic!("invalid method for request matcher"),
+        };
+        if !self.methods.contains(&method) {
+            self.methods.push(method);
+        }
+        self
+    }
+    /// Adds a header that the request must have, the key and value may be the {} wildcard
+    pub fn with_header(mut self, key : &str, value : &str) -> Self {
+        self.headers.push((key.to_string(), value.to_string()));
+        self
+    }
+    /// Adds a query argument that the request must have, the key and value may be the {} wildcard
+    pub fn with_query(mut self, key : &str, value : &str) -> Self {
+        self.queries.push((key.to_string(), value.to_string()));
+        self
+    }
+    /// Sets the scheme that the request must have, replacing any previous scheme
+    pub fn with_scheme(mut self, scheme : &str) -> Self {
+        self.scheme = Some(scheme.to_string());
+        self
+    }
+    /// Returns the path pattern, if one was set
+    pub fn path(&self) -> Option<&str> {
+        self.path.as_deref()
+    }
+    /// Returns true if the path pattern only has to match the beginning of the path
+    pub fn is_prefix(&self) -> bool {
+        self.prefix
+    }
+    /// Returns the methods that were added, an empty slice means any method is allowed
+    pub fn methods(&self) -> &[Method] {
+        &self.methods
+    }
+    /// Returns an iterator over the keys and values of the headers the request must have
+    pub fn headers(&self) -> impl '_ + Iterator<Item=(&str, &str)> {
+        self.headers.iter().map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+    /// Returns an iterator over the keys and values of the query arguments the request must have
+    pub fn queries(&self) -> impl '_ + Iterator<Item=(&str, &str)> {
+        self.queries.iter().map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+    /// Returns the scheme, if one was set
+    pub fn scheme(&self) -> Option<&str> {
+        self.scheme.as_deref()
+    }
+    /// Returns true if every request that matches the other matcher also matches this one, by
+    /// comparing the conditions of both matchers rather than any request
+    /// # Example
+    /// ```
+    /// use http_tools::request::RequestMatcher;
+    /// 
+    /// let page = RequestMatcher::new().with_path("/{}");
+    /// let health = RequestMatcher::new().with_path("/health").with_method("GET");
+    /// assert!(page.covers(&health));
+    /// assert!(!health.covers(&page));
+    /// ```
+    pub fn covers(&self, other : &RequestMatcher) -> bool {
+        covers(self, other)
+    }
+    /// Returns true if a request could match both this and the other matcher, by comparing the
+    /// path patterns, methods and schemes of both matchers
+    pub fn overlaps(&self, other : &RequestMatcher) -> bool {
+        overlaps(self, other)
+    }
+    /// Returns true if the request passes every condition of the matcher
+    pub fn matches<R>(&self, request : &Request<R>) -> bool {
+        request.filter().filter_matcher(self).is_some()
+    }
+    // Passes the request through the filter of each condition, the cheaper conditions are
+    // checked first so that most requests are turned away before the path is walked
+    pub(crate) fn apply<'r, R>(&self, filter : Option<&'r Request<R>>) -> Option<&'r Request<R>> {
+        let mut filter = filter;
+        if let Some(scheme) = &self.scheme {
+            filter = filter.filter_scheme(scheme);
+        }
+        if !self.methods.is_empty() {
+            filter = filter.filter_fn(|request| self.methods.contains(request.method()));
+        }
+        filter = match (&self.path, self.prefix) {
+            (Some(pattern), true) => filter.filter_path_prefix(pattern),
+            (Some(pattern), false) => filter.filter_path(pattern),
+            (None, _) => filter,
+        };
+        for (key, value) in &self.headers {
+            filter = filter.filter_header(key, value.as_str());
+        }
+        for (key, value) in &self.queries {
+            filter = filter.filter_query(key, value);
+        }
+        filter
+    }
+}
+
+/* ============================================================================================ */
+/*     Test Cases                                                                               */
+/* ============================================================================================ */
+#[test]
+fn test_matcher() {
+    use http::request::Builder;
+    let request = Builder::new()
+        .uri("https://www.rust-lang.org/item/rust/edition?cool=rust")
+        .method("POST")
+        .header("content-type", "application/json")
+        .body(()).unwrap();
+    assert!(RequestMatcher::new().matches(&request));
+    assert!(RequestMatcher::new().with_path("/item/{}/edition").matches(&request));
+    assert!(!RequestMatcher::new().with_path("/item/{}").matches(&request));
+    assert!(RequestMatcher::new().with_path_prefix("/item/{}").matches(&request));
+    assert!(!RequestMatcher::new().with_path_prefix("/item/{}").with_path("/item/{}").matches(&request));
+    assert!(RequestMatcher::new().with_method("GET").with_method("POST").matches(&request));
+    assert!(!RequestMatcher::new().with_method("GET").matches(&request));
+    assert!(RequestMatcher::new().with_header("content-type", "{}").matches(&request));
+    assert!(!RequestMatcher::new().with_header("content-type", "{}").with_header("accept", "{}").matches(&request));
+    assert!(RequestMatcher::new().with_query("cool", "rust").matches(&request));
+    assert!(!RequestMatcher::new().with_query("cool", "go").matches(&request));
+    assert!(RequestMatcher::new().with_scheme("https").matches(&request));
+    assert!(!RequestMatcher::new().with_scheme("http").matches(&request));
+}
+
+#[test]
+fn test_matcher_value() {
+    let first = RequestMatcher::new().with_path("/item/{}").with_method("GET").with_method("GET");
+    let second = first.clone();
+    assert_eq!(first, second);
+    assert_ne!(first, second.clone().with_query("cool", "{}"));
+    assert_eq!(first.methods(), &[Method::GET]);
+    assert_eq!(first.path(), Some("/item/{}"));
+    assert!(!first.is_prefix());
+    assert_eq!(first.headers().count(), 0);
+    assert!(format!("{:?}", first).contains("/item/{}"));
+}
+
+#[test]
+#[should_panic]
+fn test_matcher_catch_all_not_last() {
+    let _ = RequestMatcher::new().with_path("/files/{*rest}/x");
+}
@@ -24,8 +24,28 @@
 */
 mod filter;
 mod extension;
+mod diagnose;
+mod path;
+mod query;
+mod matcher;
+mod negotiation;
+mod router;
+mod routes;
+mod conflict;
+#[cfg(feature = "serde")]
+mod deserialize;
 
 pub use self::{
-    extension::{query_iter, Extension},
+    diagnose::{Diagnose, FilterRejection},
+    extension::{cookie_iter, query_iter, query_iter_decoded, Extension},
     filter::Filter,
-};
\ No newline at end of file
+    query::Query,
+    matcher::RequestMatcher,
+    negotiation::{accept, accept_encoding, accept_language, Accept, AcceptEncoding, AcceptLanguage},
+    path::{path_captures, path_captures_with, CaptureKey, Captures, Constraint, MAX_CAPTURES},
+    router::{AllowedMethods, Match, Resolution, Router},
+    routes::{Route, Routes},
+    conflict::{Conflict, ConflictKind},
+};
+#[cfg(feature = "serde")]
+pub use self::deserialize::{query_deserialize, QueryError};
\ No newline at end of file
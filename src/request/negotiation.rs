@@ -0,0 +1,406 @@
+// MIT License
+// 
+// Copyright (c) 2019 Jonathon Davis
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software. 
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+/* ============================================================================================ */
+/*     Document Structure                                                                       */
+/*          Accept                                                                              */
+/*          Accept Language                                                                     */
+/*          Accept Encoding                                                                     */
+/*          Test Cases                                                                          */
+/* ============================================================================================ */
+use http::request::Request;
+use http::header::{HeaderMap, ACCEPT_ENCODING, ACCEPT_LANGUAGE};
+use crate::header::{self, list_iter, MediaType, Quality};
+use crate::header::quality::split_quality;
+
+/* ============================================================================================ */
+/*     Accept                                                                                   */
+/* ============================================================================================ */
+
+/// Returns the media ranges of the Accept headers of the request, which can be used to pick the
+/// best media type to respond with. The headers are parsed as they are used, so no allocation is
+/// made. 
+/// # Example
+/// ```
+/// use http::request::Builder;
+/// use http_tools::request::accept;
+/// use http_tools::header::mime;
+/// 
+/// // given an http request
+/// let request = Builder::new()
+///                 .header("accept", "text/html;q=0.8, application/*;q=0.9, application/json")
+///                 .body(()).unwrap();
+/// 
+/// // the json media type is the most specific match, and has the highest quality
+/// let offered = [mime::HTML, mime::MSGPACK, mime::JSON];
+/// assert_eq!(accept(&request).negotiate(&offered), Some(mime::JSON));
+/// 
+/// // msgpack is matched by application/*
+/// assert_eq!(accept(&request).negotiate(&[mime::HTML, mime::MSGPACK]), Some(mime::MSGPACK));
+/// 
+/// // nothing offered is acceptable
+/// assert_eq!(accept(&request).negotiate(&[mime::PNG]), None);
+/// ```
+pub fn accept<R>(request : &Request<R>) -> Accept<'_> {
+    Accept { headers : request.headers() }
+}
+
+/// The media ranges of the Accept headers of a request, created by request::accept. 
+/// 
+/// When a media type is matched by more than one media range, the quality of the most specific
+/// range is used. A range with a subtype such as text/html is more specific than text/*, which is
+/// more specific than */*, and a range with more parameters is more specific than one with less.
+/// A request without an Accept header, or where none of the ranges could be parsed, accepts 
+/// every media type.
+#[derive(Debug, Clone, Copy)]
+pub struct Accept<'a> {
+    headers : &'a HeaderMap,
+}
+
+impl<'a> Accept<'a> {
+    /// Returns an iterator over the media ranges and their quality, in the order they were sent
+    pub fn iter(&self) -> impl 'a + Iterator<Item=(MediaType<'a>, Quality)> {
+        header::accept(self.headers)
+    }
+    /// Returns the quality of the media type, which is zero when it isn't acceptable. Parameters
+    /// of the media type are matched against the parameters of the media ranges.
+    pub fn quality(&self, media_type : &str) -> Quality {
+        let offered = match MediaType::parse(media_type) {
+            Some(offered) => offered,
+            None => return Quality::ZERO,
+        };
+        let mut ranges = self.iter().peekable();
+        if ranges.peek().is_none() {
+            return Quality::ONE;
+        }
+        // find the quality of the most specific range that matches
+        let mut best : Option<((u8, usize), Quality)> = None;
+        for (range, quality) in ranges {
+            if !offered.is(range.essence()) {
+                continue;
+            }
+            // every parameter of the range has to be a parameter of the media type
+            if !range.params().all(|(name, value)| offered.param(name) == Some(value)) {
+                continue;
+            }
+            let specificity = match (range.type_(), range.subtype()) {
+                ("*", _) => 0,
+                (_, "*") => 1,
+                _ => 2,
+            };
+            let specificity = (specificity, range.params().count());
+            if best.map_or(true, |(best, _)| specificity > best) {
+                best = Some((specificity, quality));
+            }
+        }
+        best.map_or(Quality::ZERO, |(_, quality)| quality)
+    }
+    /// Returns true if the media type has a quality above zero
+    pub fn accepts(&self, media_type : &str) -> bool {
+        !self.quality(media_type).is_zero()
+    }
+    /// Picks the offered media type with the highest quality, when media types have the same
+    /// quality the one offered first is picked, so offered should be in order of preference.
+    /// None is returned if none of the offered media types are acceptable, in which case a
+    /// 406 Not Acceptable can be sent or the Accept header can be ignored.
+    pub fn negotiate<'o>(&self, offered : &[&'o str]) -> Option<&'o str> {
+        negotiate(offered, |media_type| self.quality(media_type))
+    }
+}
+
+/* ============================================================================================ */
+/*     Accept Language                                                                          */
+/* ============================================================================================ */
+
+/// Returns the language ranges of the Accept-Language headers of the request, which can be used
+/// to pick the best language to respond with. The headers are parsed as they are used, so no 
+/// allocation is made. 
+/// # Example
+/// ```
+/// use http::request::Builder;
+/// use http_tools::request::accept_language;
+/// 
+/// // given an http request
+/// let request = Builder::new()
+///                 .header("accept-language", "fr-CH, fr;q=0.9, en;q=0.8, *;q=0.5")
+///                 .body(()).unwrap();
+/// 
+/// // fr matches fr-FR, and has a higher quality than en
+/// assert_eq!(accept_language(&request).negotiate(&["en-US", "fr-FR"]), Some("fr-FR"));
+/// 
+/// // de is only matched by the wildcard
+/// assert_eq!(accept_language(&request).negotiate(&["de", "en-GB"]), Some("en-GB"));
+/// ```
+pub fn accept_language<R>(request : &Request<R>) -> AcceptLanguage<'_> {
+    AcceptLanguage { headers : request.headers() }
+}
+
+/// The language ranges of the Accept-Language headers of a request, created by 
+/// request::accept_language. 
+/// 
+/// Language tags are matched using the basic filtering of RFC 4647, where a range matches a tag
+/// if it is the same as the tag, or is a prefix of the tag followed by a '-'. So the range en
+/// matches the tags en and en-US, but not eng. The '*' range matches every tag. When a tag is
+/// matched by more than one range the quality of the longest range is used. A request without
+/// an Accept-Language header, or where none of the ranges could be parsed, accepts every language.
+#[derive(Debug, Clone, Copy)]
+pub struct AcceptLanguage<'a> {
+    headers : &'a HeaderMap,
+}
+
+impl<'a> AcceptLanguage<'a> {
+    /// Returns an iterator over the language ranges and their quality, in the order they were sent
+    pub fn iter(&self) -> impl 'a + Iterator<Item=(&'a str, Quality)> {
+        list_iter(self.headers, ACCEPT_LANGUAGE)
+            .filter_map(split_quality)
+            .filter(|(range, _)| is_language_range(range))
+    }
+    /// Returns the quality of the language tag, which is zero when it isn't acceptable
+    pub fn quality(&self, tag : &str) -> Quality {
+        let mut ranges = self.iter().peekable();
+        if ranges.peek().is_none() {
+            return Quality::ONE;
+        }
+        // find the quality of the longest range that matches, the wildcard being the shortest
+        let mut best : Option<(usize, Quality)> = None;
+        for (range, quality) in ranges {
+            let length = match range {
+                "*" => 0,
+                range if tag.len() >= range.len()
+                    && tag[..range.len()].eq_ignore_ascii_case(range)
+                    && (tag.len() == range.len() || tag.as_bytes()[range.len()] == b'-') => range.len(),
+                _ => continue,
+            };
+            if best.map_or(true, |(best, _)| length > best) {
+                best = Some((length, quality));
+            }
+        }
+        best.map_or(Quality::ZERO, |(_, quality)| quality)
+    }
+    /// Returns true if the language tag has a quality above zero
+    pub fn accepts(&self, tag : &str) -> bool {
+        !self.quality(tag).is_zero()
+    }
+    /// Picks the offered language tag with the highest quality, when tags have the same quality
+    /// the one offered first is picked, so offered should be in order of preference. None is 
+    /// returned if none of the offered tags are acceptable, in which case a default language
+    /// can be used.
+    pub fn negotiate<'o>(&self, offered : &[&'o str]) -> Option<&'o str> {
+        negotiate(offered, |tag| self.quality(tag))
+    }
+}
+
+// Checks that the range is either '*' or alphanumeric subtags of 1 to 8 characters separated 
+// by '-', where the first subtag is only letters
+fn is_language_range(range : &str) -> bool {
+    range == "*" || range.split('-').enumerate().all(|(index, subtag)| {
+        (1..=8).contains(&subtag.len()) && subtag.bytes().all(|b| match index {
+            0 => b.is_ascii_alphabetic(),
+            _ => b.is_ascii_alphanumeric(),
+        })
+    })
+}
+
+/* ============================================================================================ */
+/*     Accept Encoding                                                                          */
+/* ============================================================================================ */
+
+/// Returns the content codings of the Accept-Encoding headers of the request, which can be used
+/// to pick how to compress the response body. The headers are parsed as they are used, so no
+/// allocation is made. 
+/// # Example
+/// ```
+/// use http::request::Builder;
+/// use http_tools::request::accept_encoding;
+/// 
+/// // given an http request
+/// let request = Builder::new()
+///                 .header("accept-encoding", "gzip;q=0.8, br, identity;q=0")
+///                 .body(()).unwrap();
+/// 
+/// // br has the highest quality
+/// assert_eq!(accept_encoding(&request).negotiate(&["gzip", "br", "identity"]), Some("br"));
+/// 
+/// // the request has explicitly refused an uncompressed response
+/// assert!(!accept_encoding(&request).accepts("identity"));
+/// assert_eq!(accept_encoding(&request).negotiate(&["deflate", "identity"]), None);
+/// ```
+pub fn accept_encoding<R>(request : &Request<R>) -> AcceptEncoding<'_> {
+    AcceptEncoding { headers : request.headers() }
+}
+
+/// The content codings of the Accept-Encoding headers of a request, created by 
+/// request::accept_encoding. 
+/// 
+/// Codings are compared without regard to case, and a coding that isn't listed is given the
+/// quality of the '*' coding if there is one. The identity coding, meaning no compression, is
+/// always acceptable unless it is refused with identity;q=0, or with *;q=0 when identity isn't
+/// listed. A request without an Accept-Encoding header accepts every coding.
+#[derive(Debug, Clone, Copy)]
+pub struct AcceptEncoding<'a> {
+    headers : &'a HeaderMap,
+}
+
+impl<'a> AcceptEncoding<'a> {
+    /// Returns an iterator over the content codings and their quality, in the order they were sent
+    pub fn iter(&self) -> impl 'a + Iterator<Item=(&'a str, Quality)> {
+        list_iter(self.headers, ACCEPT_ENCODING)
+            .filter_map(split_quality)
+            .filter(|(coding, _)| header::is_token(coding))
+    }
+    /// Returns the quality of the content coding, which is zero when it isn't acceptable
+    pub fn quality(&self, coding : &str) -> Quality {
+        if !self.headers.contains_key(ACCEPT_ENCODING) {
+            return Quality::ONE;
+        }
+        let mut wildcard = None;
+        for (accepted, quality) in self.iter() {
+            if accepted.eq_ignore_ascii_case(coding) {
+                return quality;
+            }
+            if accepted == "*" {
+                wildcard = Some(quality);
+            }
+        }
+        match wildcard {
+            Some(quality) => quality,
+            None if coding.eq_ignore_ascii_case("identity") => Quality::ONE,
+            None => Quality::ZERO,
+        }
+    }
+    /// Returns true if the content coding has a quality above zero
+    pub fn accepts(&self, coding : &str) -> bool {
+        !self.quality(coding).is_zero()
+    }
+    /// Picks the offered content coding with the highest quality, when codings have the same
+    /// quality the one offered first is picked, so offered should be in order of preference.
+    /// None is returned if none of the offered codings are acceptable, which happens when 
+    /// identity was refused and should be answered with a 406 Not Acceptable.
+    pub fn negotiate<'o>(&self, offered : &[&'o str]) -> Option<&'o str> {
+        negotiate(offered, |coding| self.quality(coding))
+    }
+}
+
+// Picks the offered value with the highest quality, ties are won by the value offered first
+fn negotiate<'o>(offered : &[&'o str], quality : impl Fn(&str) -> Quality) -> Option<&'o str> {
+    let mut best : Option<(&'o str, Quality)> = None;
+    for value in offered {
+        let quality = quality(value);
+        if !quality.is_zero() && best.map_or(true, |(_, best)| quality > best) {
+            best = Some((value, quality));
+        }
+    }
+    best.map(|(value, _)| value)
+}
+
+/* ============================================================================================ */
+/*     Test Cases                                                                               */
+/* ============================================================================================ */
+#[test]
+fn test_accept_quality() {
+    use http::request::Builder;
+    // the example from RFC 7231 section 5.3.2
+    let request = Builder::new()
+        .header("accept", "text/*;q=0.3, text/html;q=0.7, text/html;level=1")
+        .header("accept", "text/html;level=2;q=0.4, */*;q=0.5")
+        .body(()).unwrap();
+    let accept = accept(&request);
+    let quality = |media_type| accept.quality(media_type).thousandths();
+    assert_eq!(quality("text/html;level=1"), 1000);
+    assert_eq!(quality("text/html"), 700);
+    assert_eq!(quality("text/plain"), 300);
+    assert_eq!(quality("image/jpeg"), 500);
+    assert_eq!(quality("text/html;level=2"), 400);
+    assert_eq!(quality("text/html;level=3"), 700);
+    assert_eq!(quality("invalid"), 0);
+}
+
+#[test]
+fn test_accept_negotiate() {
+    use http::request::Builder;
+    let request = Builder::new()
+        .header("accept", "application/json;q=0.5, application/msgpack;q=0.5, text/html;q=0")
+        .body(()).unwrap();
+    let accept = accept(&request);
+    assert_eq!(accept.negotiate(&["application/msgpack", "application/json"]), Some("application/msgpack"));
+    assert_eq!(accept.negotiate(&["text/html", "application/json"]), Some("application/json"));
+    assert_eq!(accept.negotiate(&["text/html", "image/png"]), None);
+    assert_eq!(accept.negotiate(&[]), None);
+    assert!(!accept.accepts("text/html"));
+
+    // without an accept header anything is acceptable, so the first offered is picked
+    let request = Builder::new().body(()).unwrap();
+    assert_eq!(crate::request::accept(&request).negotiate(&["text/html", "application/json"]), Some("text/html"));
+    let request = Builder::new().header("accept", "garbage").body(()).unwrap();
+    assert!(crate::request::accept(&request).accepts("image/png"));
+}
+
+#[test]
+fn test_accept_language() {
+    use http::request::Builder;
+    let request = Builder::new()
+        .header("accept-language", "en-US, en;q=0.5, de-CH;q=0, *;q=0.1, not_valid")
+        .body(()).unwrap();
+    let accept = accept_language(&request);
+    assert_eq!(accept.iter().count(), 4);
+    assert_eq!(accept.quality("en-US").thousandths(), 1000);
+    assert_eq!(accept.quality("EN-us").thousandths(), 1000);
+    assert_eq!(accept.quality("en-US-x-twain").thousandths(), 1000);
+    assert_eq!(accept.quality("en-GB").thousandths(), 500);
+    assert_eq!(accept.quality("en").thousandths(), 500);
+    assert_eq!(accept.quality("eng").thousandths(), 100);
+    assert_eq!(accept.quality("de-CH").thousandths(), 0);
+    assert_eq!(accept.quality("de").thousandths(), 100);
+    assert_eq!(accept.negotiate(&["de-CH", "fr", "en-GB"]), Some("en-GB"));
+    assert_eq!(accept.negotiate(&["de-CH"]), None);
+
+    let request = Builder::new().header("accept-language", "fr").body(()).unwrap();
+    assert_eq!(accept_language(&request).negotiate(&["en", "de"]), None);
+    let request = Builder::new().body(()).unwrap();
+    assert_eq!(accept_language(&request).negotiate(&["en", "de"]), Some("en"));
+}
+
+#[test]
+fn test_accept_encoding() {
+    use http::request::Builder;
+    let request = Builder::new()
+        .header("accept-encoding", "GZIP;q=0.5, br")
+        .body(()).unwrap();
+    let accept = accept_encoding(&request);
+    assert_eq!(accept.quality("gzip").thousandths(), 500);
+    assert_eq!(accept.quality("br").thousandths(), 1000);
+    assert_eq!(accept.quality("deflate").thousandths(), 0);
+    assert_eq!(accept.quality("identity").thousandths(), 1000);
+    assert_eq!(accept.negotiate(&["gzip", "br"]), Some("br"));
+    assert_eq!(accept.negotiate(&["deflate", "identity"]), Some("identity"));
+
+    let request = Builder::new().header("accept-encoding", "gzip, *;q=0").body(()).unwrap();
+    assert!(!accept_encoding(&request).accepts("identity"));
+    assert!(accept_encoding(&request).accepts("gzip"));
+    let request = Builder::new().header("accept-encoding", "*;q=0.2").body(()).unwrap();
+    assert_eq!(accept_encoding(&request).quality("zstd").thousandths(), 200);
+
+    // an empty header only accepts identity, while a missing header accepts everything
+    let request = Builder::new().header("accept-encoding", "").body(()).unwrap();
+    assert_eq!(accept_encoding(&request).negotiate(&["gzip", "identity"]), Some("identity"));
+    let request = Builder::new().body(()).unwrap();
+    assert_eq!(accept_encoding(&request).negotiate(&["gzip", "identity"]), Some("gzip"));
+}
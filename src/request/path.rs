@@ -0,0 +1,621 @@
+// MIT License
+// 
+// Copyright (c) 2019 Jonathon Davis
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software. 
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+/* ============================================================================================ */
+/*     Document Structure                                                                       */
+/*          Captures                                                                            */
+/*          Constraints                                                                         */
+/*          Path Matching                                                                       */
+/*          Test Cases                                                                          */
+/* ============================================================================================ */
+use std::borrow::Cow;
+use http::request::Request;
+use crate::encoding::percent_decode;
+
+/// The maximum number of wildcards, including a catch all, that a pattern can contain. A path
+/// never matches a pattern with more wildcards than this, and Router::route panics on one.
+pub const MAX_CAPTURES : usize = 8;
+
+/* ============================================================================================ */
+/*     Captures                                                                                 */
+/* ============================================================================================ */
+
+/// Captures holds the path segments that were matched by the wildcard tokens of a pattern.
+/// The captured segments are slices borrowed from the request uri, and the names are slices
+/// borrowed from the pattern, so no allocation is made.
+/// 
+/// A wildcard can either be anonymous '{}' or named '{name}'. Every captured segment can be 
+/// looked up by its position, and named segments can also be looked up by their name.
+/// 
+/// A Captures has room for MAX_CAPTURES segments, so that it doesn't need to allocate. A pattern
+/// with more wildcards than that never matches, rather than dropping the segments that don't fit.
+/// # Example
+/// ```
+/// use http::request::Builder;
+/// use http_tools::request::path_captures;
+/// 
+/// // Request Builder found in http crate
+/// let request = Builder::new()
+///                     .uri("https://www.rust-lang.org/users/42/posts/7")
+///                     .body(()).unwrap();
+/// 
+/// let captures = path_captures(&request, "/users/{id}/posts/{}").unwrap();
+/// // segments can be retrieved by name
+/// assert_eq!(captures.get("id"), Some("42"));
+/// // or by position
+/// assert_eq!(captures.get(0), Some("42"));
+/// assert_eq!(captures.get(1), Some("7"));
+/// assert_eq!(captures.get(2), None);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Captures<'a> {
+    names : [&'a str; MAX_CAPTURES],
+    values : [&'a str; MAX_CAPTURES],
+    len : usize,
+}
+
+impl<'a> Captures<'a> {
+    /// Returns the matched segment for the given key. A usize key returns the segment matched
+    /// by the wildcard at that position starting at 0, a &str key returns the segment matched
+    /// by the wildcard with that name.
+    pub fn get<K : CaptureKey>(&self, key : K) -> Option<&'a str> {
+        key.find(self)
+    }
+    /// Returns the number of recorded segments
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    /// Returns true if no segments were recorded
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    /// Returns an iterator over the recorded segments in the order they appear in the path
+    pub fn iter(&self) -> impl '_ + Iterator<Item=&'a str> {
+        self.values[..self.len].iter().copied()
+    }
+    /// Returns an iterator over the names and recorded segments in the order they appear
+    /// in the path. The name is None for segments matched by the anonymous wildcard '{}'
+    pub fn iter_named(&self) -> impl '_ + Iterator<Item=(Option<&'a str>, &'a str)> {
+        self.names[..self.len].iter()
+            .zip(self.values[..self.len].iter())
+            .map(|(name, value)| (if name.is_empty() { None } else { Some(*name) }, *value))
+    }
+    // Records a matched segment, returning false if there isn't room for it
+    pub(crate) fn push(&mut self, name : &'a str, value : &'a str) -> bool {
+        if self.len == MAX_CAPTURES {
+            return false;
+        }
+        self.names[self.len] = name;
+        self.values[self.len] = value;
+        self.len += 1;
+        true
+    }
+    // Names the recorded segments in order, for when the names are only known after the
+    // segments have been recorded
+    pub(crate) fn name(&mut self, names : &'a [String]) {
+        for (slot, name) in self.names.iter_mut().zip(names) {
+            *slot = name;
+        }
+    }
+}
+
+/// A key that can be used to look up a segment in Captures. This is implemented for usize,
+/// which looks up a segment by position, and &str, which looks up a segment by name.
+pub trait CaptureKey {
+    /// Find the segment in the captures that this key refers to
+    fn find<'a>(&self, captures : &Captures<'a>) -> Option<&'a str>;
+}
+
+impl CaptureKey for usize {
+    fn find<'a>(&self, captures : &Captures<'a>) -> Option<&'a str> {
+        if *self < captures.len {
+            Some(captures.values[*self])
+        } else {
+            None
+        }
+    }
+}
+
+impl CaptureKey for &str {
+    // anonymous wildcards are stored with an empty name, so an empty key never matches
+    fn find<'a>(&self, captures : &Captures<'a>) -> Option<&'a str> {
+        if self.is_empty() {
+            return None;
+        }
+        captures.names[..captures.len].iter()
+            .position(|name| name == self)
+            .map(|index| captures.values[index])
+    }
+}
+
+/* ============================================================================================ */
+/*     Constraints                                                                              */
+/* ============================================================================================ */
+
+/// A custom constraint that can be applied to a wildcard, the first item is the name used in the 
+/// pattern and the second item is a function that returns true if the segment is allowed.
+/// # Example
+/// ```
+/// use http::request::Builder;
+/// use http_tools::request::{path_captures_with, Constraint};
+/// 
+/// fn is_slug(segment : &str) -> bool {
+///     segment.bytes().all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-')
+/// }
+/// const CONSTRAINTS : &[Constraint] = &[("slug", is_slug)];
+/// 
+/// // Request Builder found in http crate
+/// let request = Builder::new()
+///                     .uri("https://www.rust-lang.org/blog/hello-world")
+///                     .body(()).unwrap();
+/// 
+/// let captures = path_captures_with(&request, "/blog/{title:slug}", CONSTRAINTS).unwrap();
+/// assert_eq!(captures.get("title"), Some("hello-world"));
+/// ```
+pub type Constraint<'c> = (&'c str, fn(&str) -> bool);
+
+// The names of the built in constraints, which check_constraint knows how to check
+pub(crate) const BUILT_IN_CONSTRAINTS : &[&str] = &[
+    "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize", 
+    "alpha", "alnum", "hex", "uuid",
+];
+
+// Returns true if the kind is one of the custom constraints or one of the built in constraints
+pub(crate) fn is_known_constraint(kind : &str, constraints : &[Constraint]) -> bool {
+    constraints.iter().any(|(name, _)| *name == kind) || BUILT_IN_CONSTRAINTS.contains(&kind)
+}
+
+// Returns the reason the pattern can never match a path, or None if it can. A catch all has to
+// be the last segment, there can be at most MAX_CAPTURES wildcards and every constraint has to
+// be one of the custom constraints or one of the built in constraints.
+pub(crate) fn pattern_error(pattern : &str, constraints : &[Constraint]) -> Option<String> {
+    let mut segments = pattern.split('/').map(parse_segment).peekable();
+    let mut wildcards = 0;
+    while let Some(segment) = segments.next() {
+        let kind = match segment {
+            Segment::Literal(_) => continue,
+            Segment::CatchAll { .. } if segments.peek().is_some() => return Some(String::from("has a catch all that isn't the last segment")),
+            Segment::Wildcard { kind, .. } | Segment::CatchAll { kind, .. } => kind,
+        };
+        wildcards += 1;
+        if wildcards > MAX_CAPTURES {
+            return Some(format!("has more than {} wildcards", MAX_CAPTURES));
+        }
+        if let Some(kind) = kind {
+            if !is_known_constraint(kind, constraints) {
+                return Some(format!("has the unknown constraint {}", kind));
+            }
+        }
+    }
+    None
+}
+
+// check_constraint returns true if the segment satisfies the constraint with the given kind.
+// Custom constraints are checked first so that they can replace the built in ones, any
+// kind that isn't known will never match.
+pub(crate) fn check_constraint(kind : &str, segment : &str, constraints : &[Constraint]) -> bool {
+    if let Some((_, check)) = constraints.iter().find(|(name, _)| *name == kind) {
+        return check(segment);
+    }
+    match kind {
+        "u8" => segment.parse::<u8>().is_ok(),
+        "u16" => segment.parse::<u16>().is_ok(),
+        "u32" => segment.parse::<u32>().is_ok(),
+        "u64" => segment.parse::<u64>().is_ok(),
+        "u128" => segment.parse::<u128>().is_ok(),
+        "usize" => segment.parse::<usize>().is_ok(),
+        "i8" => segment.parse::<i8>().is_ok(),
+        "i16" => segment.parse::<i16>().is_ok(),
+        "i32" => segment.parse::<i32>().is_ok(),
+        "i64" => segment.parse::<i64>().is_ok(),
+        "i128" => segment.parse::<i128>().is_ok(),
+        "isize" => segment.parse::<isize>().is_ok(),
+        "alpha" => !segment.is_empty() && segment.bytes().all(|b| b.is_ascii_alphabetic()),
+        "alnum" => !segment.is_empty() && segment.bytes().all(|b| b.is_ascii_alphanumeric()),
+        "hex" => !segment.is_empty() && segment.bytes().all(|b| b.is_ascii_hexdigit()),
+        "uuid" => is_uuid(segment),
+        _ => false,
+    }
+}
+
+// A uuid is 32 hex digits in the groups 8-4-4-4-12 seperated by hyphens
+fn is_uuid(segment : &str) -> bool {
+    let bytes = segment.as_bytes();
+    bytes.len() == 36 && bytes.iter().enumerate().all(|(i, b)| match i {
+        8 | 13 | 18 | 23 => *b == b'-',
+        _ => b.is_ascii_hexdigit(),
+    })
+}
+
+/* ============================================================================================ */
+/*     Path Matching                                                                            */
+/* ============================================================================================ */
+
+/// Matches the path of the request against the pattern in the same way as Filter::filter_path,
+/// but instead of discarding the segments matched by the wildcards '{}' and '{name}' they are
+/// returned as Captures. The function returns None if the path doesn't match the pattern.
+/// 
+/// A wildcard can be constrained with a type such as '{id:u64}' or '{:uuid}', in which case the
+/// segment has to satisfy the constraint for the path to match. The built in constraints are 
+/// the integer types u8 through u128, i8 through i128, usize and isize, as well as alpha, 
+/// alnum, hex and uuid. A constraint that isn't known will never match, custom constraints can
+/// be given to path_captures_with. Router::route panics on a constraint that it doesn't know.
+/// 
+/// The last segment of a pattern can be a catch all such as '{*rest}', which matches zero or 
+/// more trailing segments and captures the remainder of the path without the leading slash.
+/// 
+/// A pattern can contain at most MAX_CAPTURES wildcards, a pattern with more never matches.
+/// # Example
+/// ```
+/// use http::request::Builder;
+/// use http_tools::request::path_captures;
+/// 
+/// // Request Builder found in http crate
+/// let request = Builder::new()
+///                     .uri("https://www.rust-lang.org/item/rust")
+///                     .body(()).unwrap();
+/// 
+/// // the wildcard matched rust
+/// let captures = path_captures(&request, "/item/{}").unwrap();
+/// assert_eq!(captures.get(0), Some("rust"));
+/// 
+/// // named wildcards can be looked up by name
+/// let captures = path_captures(&request, "/item/{name}").unwrap();
+/// assert_eq!(captures.get("name"), Some("rust"));
+/// 
+/// // the path doesn't match
+/// assert!(path_captures(&request, "/other/{}").is_none());
+/// 
+/// // the segment rust doesn't satisfy the u64 constraint
+/// assert!(path_captures(&request, "/item/{id:u64}").is_none());
+/// 
+/// // the catch all matches the rest of the path
+/// let captures = path_captures(&request, "/{*rest}").unwrap();
+/// assert_eq!(captures.get("rest"), Some("item/rust"));
+/// ```
+pub fn path_captures<'a, R>(request : &'a Request<R>, pattern : &'a str) -> Option<Captures<'a>> {
+    match_path(pattern, request.uri().path(), false, &[])
+}
+
+/// The same as path_captures, except that the given custom constraints can be used in the
+/// pattern alongside the built in constraints. A custom constraint with the same name as a 
+/// built in constraint will be used instead of the built in one.
+/// # Example
+/// ```
+/// use http::request::Builder;
+/// use http_tools::request::path_captures_with;
+/// 
+/// // Request Builder found in http crate
+/// let request = Builder::new()
+///                     .uri("https://www.rust-lang.org/item/Rust")
+///                     .body(()).unwrap();
+/// 
+/// let captures = path_captures_with(&request, "/item/{name:capital}", &[
+///     ("capital", |s| s.starts_with(char::is_uppercase)),
+/// ]);
+/// assert_eq!(captures.unwrap().get("name"), Some("Rust"));
+/// ```
+pub fn path_captures_with<'a, R>(request : &'a Request<R>, pattern : &'a str, constraints : &[Constraint]) -> Option<Captures<'a>> {
+    match_path(pattern, request.uri().path(), false, constraints)
+}
+
+// A single segment of a pattern, either text that must match exactly, a wildcard that 
+// matches any segment or a catch all that matches the rest of the path. The name of an
+// anonymous wildcard is empty, and the kind is the constraint that must be satisfied 
+// if there is one
+#[derive(Clone, Copy)]
+pub(crate) enum Segment<'a> {
+    Literal(&'a str),
+    Wildcard { name : &'a str, kind : Option<&'a str> },
+    CatchAll { name : &'a str, kind : Option<&'a str> },
+}
+
+// A segment is a wildcard if it is wrapped in braces, '{}' is anonymous and '{name}' is named.
+// Either can be followed by a colon and a constraint such as '{:u64}' or '{name:u64}'. If the
+// inside of the braces starts with a '*' such as '{*rest}' then the segment is a catch all
+pub(crate) fn parse_segment(segment : &str) -> Segment<'_> {
+    if segment.len() >= 2 && segment.starts_with('{') && segment.ends_with('}') {
+        let inner = &segment[1..segment.len() - 1];
+        let (name, kind) = match inner.find(':') {
+            Some(index) => (&inner[..index], Some(&inner[index + 1..])),
+            None => (inner, None),
+        };
+        if let Some(name) = name.strip_prefix('*') {
+            Segment::CatchAll { name, kind }
+        } else {
+            Segment::Wildcard { name, kind }
+        }
+    } else {
+        Segment::Literal(segment)
+    }
+}
+
+// Segments splits a path on the forward slash in the same way as str::split, but it also
+// keeps track of the part of the path that hasn't been split yet, so that a catch all
+// can capture the rest of the path as a single slice
+#[derive(Clone, Copy)]
+pub(crate) struct Segments<'a> {
+    rest : Option<&'a str>,
+}
+
+impl<'a> Segments<'a> {
+    pub(crate) fn new(path : &'a str) -> Self {
+        Segments { rest : Some(path) }
+    }
+    // The part of the path that the following calls to next will return
+    pub(crate) fn remainder(&self) -> Option<&'a str> {
+        self.rest
+    }
+}
+
+impl<'a> Iterator for Segments<'a> {
+    type Item = &'a str;
+    fn next(&mut self) -> Option<&'a str> {
+        let rest = self.rest?;
+        match rest.find('/') {
+            Some(index) => {
+                self.rest = Some(&rest[index + 1..]);
+                Some(&rest[..index])
+            }
+            None => {
+                self.rest = None;
+                Some(rest)
+            }
+        }
+    }
+}
+
+// The segments where a path and a pattern stopped matching, expected is None if the pattern
+// ended before the path, and actual is None if the path ended before the pattern
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct PathMismatch<'a> {
+    pub(crate) expected : Option<&'a str>,
+    pub(crate) actual : Option<&'a str>,
+}
+
+// match_path walks the pattern and the path one segment at a time, both split on the forward
+// slash. If prefix is false then the path and pattern must have the same number of segments,
+// if prefix is true then the path only needs to begin with the pattern. Any segment matched 
+// by a wildcard is recorded in the returned Captures, if a wildcard has a constraint the
+// segment must also satisfy it. A catch all must be the last segment of the pattern, and
+// records the rest of the path which may be empty.
+pub(crate) fn match_path<'a>(pattern : &'a str, path : &'a str, prefix : bool, constraints : &[Constraint]) -> Option<Captures<'a>> {
+    try_match_path(pattern, path, prefix, constraints).ok()
+}
+
+// try_match_path does the work of match_path, but when the path doesn't match it reports the
+// segments that didn't match
+pub(crate) fn try_match_path<'a>(pattern : &'a str, path : &'a str, prefix : bool, constraints : &[Constraint]) -> Result<Captures<'a>, PathMismatch<'a>> {
+    walk_path(pattern, path, prefix, constraints, false)
+}
+
+// match_path_decoded matches in the same way as match_path, except that each segment of the path
+// is percent decoded before it is compared to the pattern or checked against a constraint. The
+// path is still split on the forward slash before decoding, so an encoded %2F doesn't split it.
+pub(crate) fn match_path_decoded(pattern : &str, path : &str, prefix : bool, constraints : &[Constraint]) -> bool {
+    walk_path(pattern, path, prefix, constraints, true).is_ok()
+}
+
+// Returns the segment percent decoded if decode is true, otherwise the segment as it is
+fn decoded(segment : &str, decode : bool) -> Cow<'_, str> {
+    if decode {
+        percent_decode(segment)
+    } else {
+        Cow::Borrowed(segment)
+    }
+}
+
+// walk_path is shared by all of the path matching functions, it returns the Captures if the
+// path matches the pattern or the segments that didn't match otherwise
+fn walk_path<'a>(pattern : &'a str, path : &'a str, prefix : bool, constraints : &[Constraint], decode : bool) -> Result<Captures<'a>, PathMismatch<'a>> {
+    let mut captures = Captures::default();
+    let mismatch = |expected, actual| Err(PathMismatch { expected, actual });
+    // create two iterators split on the forward slash for both
+    // the pattern given as an argument and the actual path of 
+    // the request being filtered
+    let mut split_pattern = pattern.split('/');
+    let mut split_path = Segments::new(path);
+    loop {
+        // the remainder has to be taken before the path is advanced, in case the 
+        // pattern is a catch all
+        let remainder = split_path.remainder().unwrap_or("");
+        // call next on each of the iterators
+        let pattern_item = split_pattern.next();
+        let path_item = split_path.next();
+        match (pattern_item.map(parse_segment), path_item) {
+            // a catch all matches the rest of the path, even if there is nothing left
+            (Some(Segment::CatchAll { name, kind }), _) => {
+                // the catch all has to be the last segment of the pattern
+                if let Some(next) = split_pattern.next() {
+                    return mismatch(Some(next), None);
+                }
+                if let Some(kind) = kind {
+                    if !check_constraint(kind, &decoded(remainder, decode), constraints) {
+                        return mismatch(pattern_item, Some(remainder));
+                    }
+                }
+                // a pattern with more wildcards than fit in the captures never matches
+                if !captures.push(name, remainder) {
+                    return mismatch(pattern_item, Some(remainder));
+                }
+                return Ok(captures);
+            }
+            // since we are using the split operator if the pattern ends with a /
+            // then there will be a lingering "". check to make sure it is a lingering
+            // "" and not one in the middle of the pattern
+            (Some(Segment::Literal("")), Some(_)) if prefix && path_item != Some("") => {
+                return match split_pattern.next() {
+                    Some(_) => mismatch(pattern_item, path_item),
+                    None => Ok(captures),
+                }
+            }
+            // if they both have a result check to see if they equal eachother
+            (Some(Segment::Literal(pattern)), Some(path)) if pattern != decoded(path, decode) => return mismatch(pattern_item, path_item),
+            (Some(Segment::Literal(_)), Some(_)) => (),
+            // a constrained wildcard only matches segments that satisfy the constraint
+            (Some(Segment::Wildcard { kind : Some(kind), .. }), Some(path)) if !check_constraint(kind, &decoded(path, decode), constraints) => return mismatch(pattern_item, path_item),
+            // the wildcard matches any segment, record what it matched
+            (Some(Segment::Wildcard { name, .. }), Some(path)) => if !captures.push(name, path) {
+                return mismatch(pattern_item, path_item);
+            },
+            // if the path ends before the pattern then they are not equal, and the pattern is not a prefix
+            (Some(_), None) => return mismatch(pattern_item, None),
+            // if the pattern ends before the path then it's only a match if looking for a prefix
+            (None, Some(_)) => return if prefix { Ok(captures) } else { mismatch(None, path_item) },
+            // if both the pattern and path end at the same time then they have been equal up to this point
+            // and are assumed to be equal
+            (None, None) => return Ok(captures),
+        }
+    }
+}
+
+/* ============================================================================================ */
+/*     Test Cases                                                                               */
+/* ============================================================================================ */
+#[test]
+fn test_path_captures() {
+    use http::request::Builder;
+    let request = Builder::new().uri("https://www.rust-lang.org/item/42/part/7").body(()).unwrap();
+    let captures = path_captures(&request, "/item/{}/part/{}").unwrap();
+    assert_eq!(captures.len(), 2);
+    assert_eq!(captures.get(0), Some("42"));
+    assert_eq!(captures.get(1), Some("7"));
+    assert_eq!(captures.iter().collect::<Vec<_>>(), vec!["42", "7"]);
+    let captures = path_captures(&request, "/item/42/part/7").unwrap();
+    assert!(captures.is_empty());
+    assert!(path_captures(&request, "/item/{}").is_none());
+    assert!(path_captures(&request, "/item/{}/other/{}").is_none());
+}
+
+#[test]
+fn test_named_captures() {
+    use http::request::Builder;
+    let request = Builder::new().uri("https://www.rust-lang.org/users/42/posts/7").body(()).unwrap();
+    let captures = path_captures(&request, "/users/{id}/posts/{post_id}").unwrap();
+    assert_eq!(captures.get("id"), Some("42"));
+    assert_eq!(captures.get("post_id"), Some("7"));
+    assert_eq!(captures.get(1), Some("7"));
+    assert_eq!(captures.get("missing"), None);
+    let captures = path_captures(&request, "/users/{}/posts/{post_id}").unwrap();
+    assert_eq!(captures.get(""), None);
+    assert_eq!(captures.iter_named().collect::<Vec<_>>(), vec![(None, "42"), (Some("post_id"), "7")]);
+}
+
+#[test]
+fn test_constrained_captures() {
+    use http::request::Builder;
+    let request = Builder::new().uri("https://www.rust-lang.org/item/42/67e55044-10b1-426f-9247-bb680e5fe0c8").body(()).unwrap();
+    let captures = path_captures(&request, "/item/{id:u64}/{key:uuid}").unwrap();
+    assert_eq!(captures.get("id"), Some("42"));
+    assert!(path_captures(&request, "/item/{:u8}/{:uuid}").is_some());
+    assert!(path_captures(&request, "/item/{:alpha}/{}").is_none());
+    assert!(path_captures(&request, "/item/{:unknown}/{}").is_none());
+    let request = Builder::new().uri("https://www.rust-lang.org/item/300/abc").body(()).unwrap();
+    assert!(path_captures(&request, "/item/{:u8}/{}").is_none());
+    assert!(path_captures(&request, "/item/{:i16}/{:alpha}").is_some());
+    assert!(path_captures(&request, "/item/{}/{:uuid}").is_none());
+    assert!(path_captures_with(&request, "/item/{}/{:abc}", &[("abc", |s| s == "abc")]).is_some());
+    assert!(path_captures_with(&request, "/item/{:u8}/{}", &[("u8", |_| true)]).is_some());
+}
+
+#[test]
+fn test_catch_all_captures() {
+    use http::request::Builder;
+    let request = Builder::new().uri("https://www.rust-lang.org/static/css/site.css").body(()).unwrap();
+    let captures = path_captures(&request, "/static/{*rest}").unwrap();
+    assert_eq!(captures.get("rest"), Some("css/site.css"));
+    let captures = path_captures(&request, "/{}/{*}").unwrap();
+    assert_eq!(captures.get(0), Some("static"));
+    assert_eq!(captures.get(1), Some("css/site.css"));
+    assert!(path_captures(&request, "/{*rest}/site.css").is_none());
+    assert!(path_captures(&request, "/other/{*rest}").is_none());
+    assert!(path_captures(&request, "/static/{*rest:alpha}").is_none());
+    let request = Builder::new().uri("https://www.rust-lang.org/static").body(()).unwrap();
+    assert_eq!(path_captures(&request, "/static/{*rest}").unwrap().get("rest"), Some(""));
+    let request = Builder::new().uri("https://www.rust-lang.org/static/").body(()).unwrap();
+    assert_eq!(path_captures(&request, "/static/{*rest}").unwrap().get("rest"), Some(""));
+    let request = Builder::new().uri("https://www.rust-lang.org/staticfiles").body(()).unwrap();
+    assert!(path_captures(&request, "/static/{*rest}").is_none());
+}
+
+#[test]
+fn test_pattern_error() {
+    assert_eq!(pattern_error("/files/{*rest}", &[]), None);
+    assert_eq!(pattern_error("/files/{*rest}/x", &[]), Some(String::from("has a catch all that isn't the last segment")));
+    assert_eq!(pattern_error("/{}/{}/{}/{}/{}/{}/{}/{}/{}", &[]), Some(String::from("has more than 8 wildcards")));
+    assert_eq!(pattern_error("/users/{id:u46}", &[]), Some(String::from("has the unknown constraint u46")));
+    assert_eq!(pattern_error("/blog/{:slug}", &[("slug", |_| true)]), None);
+}
+
+#[test]
+fn test_known_constraint() {
+    assert!(is_known_constraint("u64", &[]));
+    assert!(!is_known_constraint("u46", &[]));
+    assert!(is_known_constraint("slug", &[("slug", |_| true)]));
+    assert!(BUILT_IN_CONSTRAINTS.iter().all(|kind| check_constraint(kind, "0", &[]) || check_constraint(kind, "a", &[]) 
+        || check_constraint(kind, "00000000-0000-0000-0000-000000000000", &[])));
+}
+
+#[test]
+fn test_path_captures_capacity() {
+    use http::request::Builder;
+    let request = Builder::new().uri("https://www.rust-lang.org/1/2/3/4/5/6/7/8/9").body(()).unwrap();
+    let captures = path_captures(&request, "/{}/{}/{}/{}/{}/{}/{}/{}/9").unwrap();
+    assert_eq!(captures.len(), MAX_CAPTURES);
+    assert_eq!(captures.get(7), Some("8"));
+    // a ninth wildcard would be dropped, so the path doesn't match rather than losing the segment
+    assert!(path_captures(&request, "/{}/{}/{}/{}/{}/{}/{}/{}/{name}").is_none());
+    assert!(path_captures(&request, "/{}/{}/{}/{}/{}/{}/{}/{}/{*rest}").is_none());
+    assert!(match_path("/{}/{}/{}/{}/{}/{}/{}/{}/{}", "/1/2/3/4/5/6/7/8/9/10", true, &[]).is_none());
+}
+
+#[test]
+fn test_match_path_prefix() {
+    let captures = match_path("/{}/is", "/this/is/longer", true, &[]).unwrap();
+    assert_eq!(captures.get(0), Some("this"));
+    assert!(match_path("/", "/this/is/longer", true, &[]).is_some());
+    assert!(match_path("/th", "/this/is/longer", true, &[]).is_none());
+    assert!(match_path("/this/is/longer/still", "/this/is/longer", true, &[]).is_none());
+    let captures = match_path("/this/{*rest}", "/this/is/longer", true, &[]).unwrap();
+    assert_eq!(captures.get("rest"), Some("is/longer"));
+}
+
+#[test]
+fn test_match_path_decoded() {
+    assert!(match_path_decoded("/files/my file", "/files/my%20file", false, &[]));
+    assert!(match_path_decoded("/files/{:alpha}", "/files/%61bc", false, &[]));
+    assert!(match_path_decoded("/files/a+b", "/files/a+b", false, &[]));
+    assert!(!match_path_decoded("/files/a/b", "/files/a%2Fb", false, &[]));
+    assert!(match_path_decoded("/files/{}", "/files/a%2Fb", false, &[]));
+    assert!(match_path_decoded("/my files", "/my%20files/more", true, &[]));
+    assert!(match_path("/files/my file", "/files/my%20file", false, &[]).is_none());
+}
+
+#[test]
+fn test_path_mismatch() {
+    let mismatch = |expected, actual| Err(PathMismatch { expected, actual });
+    assert_eq!(try_match_path("/this/is", "/this/was", false, &[]), mismatch(Some("is"), Some("was")));
+    assert_eq!(try_match_path("/this/is/longer", "/this/is", false, &[]), mismatch(Some("longer"), None));
+    assert_eq!(try_match_path("/this", "/this/is", false, &[]), mismatch(None, Some("is")));
+    assert_eq!(try_match_path("/{:u8}", "/300", false, &[]), mismatch(Some("{:u8}"), Some("300")));
+    assert_eq!(try_match_path("/{*rest}/more", "/this", false, &[]), mismatch(Some("more"), None));
+}
@@ -0,0 +1,140 @@
+// MIT License
+// 
+// Copyright (c) 2019 Jonathon Davis
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software. 
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+/* ============================================================================================ */
+/*     Document Structure                                                                       */
+/*          Query                                                                               */
+/*          Test Cases                                                                          */
+/* ============================================================================================ */
+use http::request::Request;
+
+/* ============================================================================================ */
+/*     Query                                                                                    */
+/* ============================================================================================ */
+
+/// Query provides lookups over the query string of a request without allocating, every key and
+/// value is a slice borrowed from the uri. The query is split into pairs on '&', and each pair is
+/// split into a key and value on the first '=', so 'a=b=c' has the key 'a' and the value 'b=c'. 
+/// A pair without an '=' such as 'debug' is a flag, it has the key 'debug' and no value. Empty
+/// pairs such as the one in 'a=b&&c=d' are skipped.
+/// # Example
+/// ```
+/// use http::request::Builder;
+/// use http_tools::request::Query;
+/// 
+/// // given an http request
+/// let request = Builder::new()
+///                 .uri("https://www.rust-lang.org/?tag=a&tag=b&debug&eq=a=b")
+///                 .body(()).unwrap();
+/// 
+/// let query = Query::new(&request);
+/// assert_eq!(query.get("tag"), Some("a"));
+/// assert_eq!(query.get_all("tag").collect::<Vec<_>>(), vec!["a", "b"]);
+/// assert_eq!(query.get("eq"), Some("a=b"));
+/// 
+/// // flags have no value, but are still present
+/// assert!(query.contains("debug"));
+/// assert_eq!(query.get("debug"), Some(""));
+/// assert!(query.pairs().any(|pair| pair == ("debug", None)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Query<'a> {
+    query : &'a str,
+}
+
+impl<'a> Query<'a> {
+    /// Creates a Query over the query string of the request, a request without a query string
+    /// results in an empty Query
+    pub fn new<R>(request : &'a Request<R>) -> Self {
+        Query::parse(request.uri().query().unwrap_or(""))
+    }
+    /// Creates a Query over a query string that isn't part of a request, such as the body of
+    /// an application/x-www-form-urlencoded form. The string should not include the leading '?'
+    pub fn parse(query : &'a str) -> Self {
+        Query { query }
+    }
+    /// Returns the query string this Query was created from
+    pub fn as_str(&self) -> &'a str {
+        self.query
+    }
+    /// Returns an iterator over the keys and values of the query, where the value is None for flags
+    pub fn pairs(&self) -> impl 'a + Iterator<Item=(&'a str, Option<&'a str>)> {
+        self.query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| match pair.find('=') {
+                Some(index) => (&pair[..index], Some(&pair[index + 1..])),
+                None => (pair, None),
+            })
+    }
+    /// Returns an iterator over the keys and values of the query, where flags have an empty value
+    pub fn iter(&self) -> impl 'a + Iterator<Item=(&'a str, &'a str)> {
+        self.pairs().map(|(key, value)| (key, value.unwrap_or("")))
+    }
+    /// Returns the first value for the key, a flag has an empty value. None is returned if the
+    /// key isn't present.
+    pub fn get(&self, key : &str) -> Option<&'a str> {
+        self.iter().find(|(k, _)| *k == key).map(|(_, value)| value)
+    }
+    /// Returns an iterator over every value for the key in the order they appear, for example
+    /// 'tag=a&tag=b' has the values 'a' and 'b' for the key 'tag'
+    pub fn get_all<'k>(&self, key : &'k str) -> impl 'k + Iterator<Item=&'a str> where 'a : 'k {
+        self.iter().filter(move |(k, _)| *k == key).map(|(_, value)| value)
+    }
+    /// Returns true if the key is present in the query, either with a value or as a flag
+    pub fn contains(&self, key : &str) -> bool {
+        self.pairs().any(|(k, _)| k == key)
+    }
+}
+
+/* ============================================================================================ */
+/*     Test Cases                                                                               */
+/* ============================================================================================ */
+#[test]
+fn test_query_pairs() {
+    let query = Query::parse("a=b&&debug&flag=&eq=a=b&=empty");
+    let pairs = query.pairs().collect::<Vec<_>>();
+    assert_eq!(pairs, vec![
+        ("a", Some("b")),
+        ("debug", None),
+        ("flag", Some("")),
+        ("eq", Some("a=b")),
+        ("", Some("empty")),
+    ]);
+    assert_eq!(Query::parse("").pairs().count(), 0);
+}
+
+#[test]
+fn test_query_lookups() {
+    use http::request::Builder;
+    let request = Builder::new().uri("https://www.rust-lang.org/?tag=a&debug&tag=b").body(()).unwrap();
+    let query = Query::new(&request);
+    assert_eq!(query.get("tag"), Some("a"));
+    assert_eq!(query.get_all("tag").collect::<Vec<_>>(), vec!["a", "b"]);
+    assert_eq!(query.get("debug"), Some(""));
+    assert_eq!(query.get("missing"), None);
+    assert_eq!(query.get_all("missing").count(), 0);
+    assert!(query.contains("debug"));
+    assert!(!query.contains("missing"));
+    let request = Builder::new().uri("https://www.rust-lang.org/").body(()).unwrap();
+    assert_eq!(Query::new(&request).iter().count(), 0);
+}
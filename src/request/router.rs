@@ -0,0 +1,735 @@
+// MIT License
+// 
+// Copyright (c) 2019 Jonathon Davis
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software. 
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+/* ============================================================================================ */
+/*     Document Structure                                                                       */
+/*          Router                                                                              */
+/*          Route Tree                                                                          */
+/*          Test Cases                                                                          */
+/* ============================================================================================ */
+use std::collections::HashMap;
+use http::request::Request;
+use http::response::{Builder, Response};
+use http::method::Method;
+use http::header::{HeaderValue, ALLOW};
+use http::status::StatusCode;
+use http::HttpTryFrom;
+use crate::request::path::{check_constraint, parse_segment, pattern_error, Captures, Constraint, Segment, Segments};
+
+// The methods that the router answers automatically, kept in statics so that they can be
+// borrowed for as long as the router
+static HEAD : Method = Method::HEAD;
+static OPTIONS : Method = Method::OPTIONS;
+
+/* ============================================================================================ */
+/*     Router                                                                                   */
+/* ============================================================================================ */
+
+/// A Router compiles a set of routes into a tree of path segments once, so that a request can
+/// be dispatched to its handler in a single walk over the path instead of testing each route
+/// in turn. Each route is made up of a method and a pattern, the patterns use the same syntax
+/// as Filter::filter_path, including named wildcards, constraints and catch alls. 
+/// 
+/// The router doesn't place any requirements on the handler type, dispatching simply returns a
+/// refrence to the handler along with the Captures from the path. When more than one route
+/// could match a path, literal segments are preferred over wildcards, and wildcards are preferred
+/// over catch alls, wildcards at the same position are tried in the order they were added.
+/// 
+/// A HEAD request to a route without a HEAD handler is dispatched to the GET handler of the route,
+/// and an OPTIONS request to a route without an OPTIONS handler is resolved to the list of methods
+/// the route allows. Router::handle takes care of both when the handlers are functions.
+/// 
+/// Building a Router allocates, but dispatching a request does not.
+/// # Example
+/// ```
+/// use http::request::{Builder, Request};
+/// use http::response::Response;
+/// use http_tools::request::{Captures, Router};
+/// 
+/// type Handler = fn(&Request<()>, Captures) -> Response<()>;
+/// 
+/// fn get_user(_req : &Request<()>, captures : Captures) -> Response<()> {
+///     Response::builder().header("user", captures.get("id").unwrap()).body(()).unwrap()
+/// }
+/// fn new_user(_req : &Request<()>, _captures : Captures) -> Response<()> {
+///     Response::builder().status(201).body(()).unwrap()
+/// }
+/// 
+/// let router = Router::<Handler>::new()
+///     .route("GET", "/users/{id:u64}", get_user)
+///     .route("POST", "/users", new_user);
+/// 
+/// let request = Builder::new()
+///                 .uri("https://www.rust-lang.org/users/42")
+///                 .body(()).unwrap();
+/// 
+/// let found = router.dispatch(&request).unwrap();
+/// let response = (found.handler())(&request, found.captures());
+/// assert_eq!(response.headers()["user"], "42");
+/// ```
+pub struct Router<H> {
+    root : Node<H>,
+    constraints : Vec<Constraint<'static>>,
+}
+
+/// The result of dispatching a request with a Router, holding the handler of the matching
+/// route along with the segments captured from the path.
+#[derive(Debug)]
+pub struct Match<'a, H> {
+    handler : &'a H,
+    captures : Captures<'a>,
+    strip_body : bool,
+}
+
+impl<'a, H> Match<'a, H> {
+    /// Returns the handler of the matching route
+    pub fn handler(&self) -> &'a H {
+        self.handler
+    }
+    /// Returns the segments captured by the wildcards of the matching route
+    pub fn captures(&self) -> Captures<'a> {
+        self.captures
+    }
+    /// Returns true if the request was a HEAD request that was dispatched to the GET handler of
+    /// the route, in which case the body of the response should be dropped.
+    pub fn strip_body(&self) -> bool {
+        self.strip_body
+    }
+}
+
+/// The result of resolving a request with a Router. Unlike Router::dispatch, resolving tells apart
+/// a request whose path didn't match any route from a request whose path matched but whose method
+/// didn't.
+// Found holds the Captures inline so that a successful resolve doesn't need to allocate
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug)]
+pub enum Resolution<'a, H> {
+    /// A route matched both the path and the method of the request
+    Found(Match<'a, H>),
+    /// At least one route matched the path of the request, but none of them had its method
+    MethodNotAllowed(AllowedMethods<'a>),
+    /// The request was an OPTIONS request and at least one route matched the path, but none of
+    /// them had an OPTIONS handler. Holds the methods those routes allow.
+    Options(AllowedMethods<'a>),
+    /// No route matched the path of the request
+    NotFound,
+}
+
+impl<'a, H> Resolution<'a, H> {
+    /// Returns a response Builder for a request that wasn't dispatched to a handler. NotFound results
+    /// in a 404 Not Found, MethodNotAllowed results in a 405 Method Not Allowed and Options results 
+    /// in a 204 No Content, both with an Allow header listing the methods of the routes that 
+    /// matched the path. Found returns None.
+    /// # Example
+    /// ```
+    /// use http::request::Builder;
+    /// use http_tools::request::Router;
+    /// 
+    /// let router = Router::new()
+    ///     .route("GET", "/users", "list_users")
+    ///     .route("POST", "/users", "new_user");
+    /// 
+    /// let request = Builder::new()
+    ///                 .method("DELETE")
+    ///                 .uri("https://www.rust-lang.org/users")
+    ///                 .body(()).unwrap();
+    /// 
+    /// let response = router.resolve(&request).default_response().unwrap().body(()).unwrap();
+    /// assert_eq!(response.status(), 405);
+    /// assert_eq!(response.headers()["allow"], "GET, HEAD, POST, OPTIONS");
+    /// ```
+    pub fn default_response(&self) -> Option<Builder> {
+        let mut builder = Builder::new();
+        match self {
+            Resolution::Found(_) => return None,
+            Resolution::NotFound => builder.status(StatusCode::NOT_FOUND),
+            Resolution::MethodNotAllowed(allowed) => builder
+                .status(StatusCode::METHOD_NOT_ALLOWED)
+                .header(ALLOW, allowed.header_value()),
+            Resolution::Options(allowed) => builder
+                .status(StatusCode::NO_CONTENT)
+                .header(ALLOW, allowed.header_value()),
+        };
+        Some(builder)
+    }
+}
+
+/// The methods of the routes that matched the path of a request, in the order they were found.
+/// HEAD is included whenever GET is, and OPTIONS is always included last as the router answers
+/// both automatically. This is only created when a request can't be dispatched, so unlike a 
+/// dispatch it allocates.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct AllowedMethods<'a> {
+    methods : Vec<&'a Method>,
+}
+
+impl<'a> AllowedMethods<'a> {
+    /// Returns true if the method is one of the allowed methods
+    pub fn contains(&self, method : &Method) -> bool {
+        self.methods.contains(&method)
+    }
+    /// Returns an iterator over the allowed methods
+    pub fn iter(&self) -> impl '_ + Iterator<Item=&'a Method> {
+        self.methods.iter().copied()
+    }
+    /// Returns true if there are no allowed methods
+    pub fn is_empty(&self) -> bool {
+        self.methods.is_empty()
+    }
+    /// Returns the allowed methods as the value of an Allow header, for example "GET, POST"
+    pub fn header_value(&self) -> HeaderValue {
+        let value = self.methods.iter().map(|m| m.as_str()).collect::<Vec<_>>().join(", ");
+        // methods are tokens, so joining them with commas is always a valid header value
+        HeaderValue::from_str(&value).expect("methods are valid header values")
+    }
+    // Adds a method if it isn't already present
+    fn insert(&mut self, method : &'a Method) {
+        if !self.contains(method) {
+            self.methods.push(method);
+        }
+    }
+}
+
+impl<H> Router<H> {
+    /// Creates an empty Router
+    pub fn new() -> Self {
+        Router {
+            root : Node::new(),
+            constraints : Vec::new(),
+        }
+    }
+    /// Adds a route to the router, requests with the given method whose path matches the pattern
+    /// will be dispatched to the handler.
+    /// # Panics
+    /// Panics if the method is not a valid http method, if a catch all is not the last segment of
+    /// the pattern, if the pattern has more than MAX_CAPTURES wildcards, if a wildcard has a
+    /// constraint that is neither built in nor added with Router::constraint beforehand, or if the 
+    /// same method and pattern have already been added.
+    pub fn route<M>(mut self, method : M, pattern : &str, handler : H) -> Self where Method : HttpTryFrom<M> {
+        let method = match Method::try_from(method) {
+            Ok(method) => method,
+            Err(_) => panic!("invalid method for route {}", pattern),
+        };
+        // a pattern that can never match is most likely a mistake, such as a typo in a constraint
+        if let Some(error) = pattern_error(pattern, &self.constraints) {
+            panic!("the route {} {}", pattern, error);
+        }
+        // routes that only differ by the names of their wildcards match the same requests
+        let endpoint = self.root.insert(pattern);
+        if endpoint.handlers.iter().any(|(m, _, _)| *m == method) {
+            panic!("the route {} {} was added more than once", method, pattern);
+        }
+        endpoint.handlers.push((method, capture_names(pattern), handler));
+        self
+    }
+    /// Adds a custom constraint that can be used by the wildcards of any route, in the same way
+    /// as the constraints given to Filter::filter_path_with. The constraint has to be added before
+    /// the routes that use it.
+    pub fn constraint(mut self, name : &'static str, check : fn(&str) -> bool) -> Self {
+        self.constraints.push((name, check));
+        self
+    }
+    /// Finds the route that matches the method and path of the request, returning None if there 
+    /// isn't one. Use Router::resolve to find out why a request couldn't be dispatched.
+    pub fn dispatch<'a, R>(&'a self, request : &'a Request<R>) -> Option<Match<'a, H>> {
+        let method = request.method();
+        let mut found = None;
+        self.root.walk(Segments::new(request.uri().path()), Captures::default(), &self.constraints, &mut |endpoint, captures| {
+            match endpoint.handler(method) {
+                Some((handler, names, strip_body)) => {
+                    let mut captures = captures;
+                    captures.name(names);
+                    found = Some(Match { handler, captures, strip_body });
+                    true
+                }
+                None => false,
+            }
+        });
+        found
+    }
+    /// Finds the route that matches the method and path of the request in the same way as dispatch,
+    /// but if there isn't one it reports whether any route matched the path, along with the methods
+    /// those routes allow. An OPTIONS request without a matching handler resolves to Options.
+    /// # Example
+    /// ```
+    /// use http::request::Builder;
+    /// use http_tools::request::{Resolution, Router};
+    /// 
+    /// let router = Router::new()
+    ///     .route("GET", "/users", "list_users");
+    /// 
+    /// let request = Builder::new()
+    ///                 .method("POST")
+    ///                 .uri("https://www.rust-lang.org/users")
+    ///                 .body(()).unwrap();
+    /// 
+    /// match router.resolve(&request) {
+    ///     Resolution::MethodNotAllowed(allowed) => assert!(allowed.contains(&http::Method::GET)),
+    ///     _ => panic!("expected the method to not be allowed"),
+    /// }
+    /// ```
+    pub fn resolve<'a, R>(&'a self, request : &'a Request<R>) -> Resolution<'a, H> {
+        let method = request.method();
+        let mut found = None;
+        let mut allowed = AllowedMethods::default();
+        self.root.walk(Segments::new(request.uri().path()), Captures::default(), &self.constraints, &mut |endpoint, captures| {
+            match endpoint.handler(method) {
+                Some((handler, names, strip_body)) => {
+                    let mut captures = captures;
+                    captures.name(names);
+                    found = Some(Match { handler, captures, strip_body });
+                    true
+                }
+                // the path matched but the method didn't, so remember which methods would have
+                None => {
+                    endpoint.allow(&mut allowed);
+                    false
+                }
+            }
+        });
+        match found {
+            Some(found) => Resolution::Found(found),
+            None if allowed.is_empty() => Resolution::NotFound,
+            None => {
+                allowed.insert(&OPTIONS);
+                if *method == Method::OPTIONS {
+                    Resolution::Options(allowed)
+                } else {
+                    Resolution::MethodNotAllowed(allowed)
+                }
+            }
+        }
+    }
+    /// Resolves the request and calls the handler of the matching route with the request and
+    /// the captured path segments. When there isn't a matching handler the response is the
+    /// Resolution::default_response with an empty body, and when a HEAD request is dispatched to
+    /// a GET handler the body of the response is replaced with an empty body.
+    /// # Example
+    /// ```
+    /// use http::request::{Builder, Request};
+    /// use http::response::Response;
+    /// use http_tools::request::{Captures, Router};
+    /// 
+    /// fn get_user(_req : &Request<()>, captures : Captures) -> Response<String> {
+    ///     Response::builder().body(captures.get("id").unwrap().to_string()).unwrap()
+    /// }
+    /// 
+    /// let router = Router::new().route("GET", "/users/{id}", get_user);
+    /// 
+    /// let request = Builder::new().uri("/users/42").body(()).unwrap();
+    /// assert_eq!(router.handle(&request).body(), "42");
+    /// 
+    /// // HEAD is answered by the GET handler without the body
+    /// let request = Builder::new().method("HEAD").uri("/users/42").body(()).unwrap();
+    /// assert_eq!(router.handle(&request).body(), "");
+    /// 
+    /// // OPTIONS is answered with the allowed methods
+    /// let request = Builder::new().method("OPTIONS").uri("/users/42").body(()).unwrap();
+    /// assert_eq!(router.handle(&request).headers()["allow"], "GET, HEAD, OPTIONS");
+    /// ```
+    pub fn handle<R, B>(&self, request : &Request<R>) -> Response<B> 
+        where H : Fn(&Request<R>, Captures) -> Response<B>, B : Default {
+        match self.resolve(request) {
+            Resolution::Found(found) => {
+                let response = (found.handler)(request, found.captures);
+                if found.strip_body {
+                    let (parts, _) = response.into_parts();
+                    Response::from_parts(parts, B::default())
+                } else {
+                    response
+                }
+            }
+            resolution => resolution.default_response()
+                .map(|mut builder| builder.body(B::default()))
+                .expect("only Found is without a default response")
+                .expect("the default response is always valid"),
+        }
+    }
+}
+
+impl<H> Default for Router<H> {
+    fn default() -> Self {
+        Router::new()
+    }
+}
+
+/* ============================================================================================ */
+/*     Route Tree                                                                               */
+/* ============================================================================================ */
+
+// A node in the route tree represents a single segment of a path. The children of the node
+// represent the next segment, and are split up by how they match so that the literals can
+// be looked up directly and the wildcards only need to be tested when the literals fail.
+struct Node<H> {
+    literals : HashMap<String, Node<H>>,
+    wildcards : Vec<(Param, Node<H>)>,
+    catch_alls : Vec<(Param, Endpoint<H>)>,
+    endpoint : Option<Endpoint<H>>,
+}
+
+// The constraint of a wildcard or catch all. The names of the wildcards are kept with the
+// handlers instead, so that patterns which only differ by their names share the same nodes.
+#[derive(PartialEq)]
+struct Param {
+    kind : Option<String>,
+}
+
+impl Param {
+    fn new(kind : Option<&str>) -> Self {
+        Param { kind : kind.map(str::to_string) }
+    }
+    // a param without a constraint accepts anything
+    fn accepts(&self, value : &str, constraints : &[Constraint]) -> bool {
+        match &self.kind {
+            Some(kind) => check_constraint(kind, value, constraints),
+            None => true,
+        }
+    }
+}
+
+// The handlers for each method registered at the end of a pattern, along with the names of
+// the wildcards in the pattern they were registered with
+struct Endpoint<H> {
+    handlers : Vec<(Method, Vec<String>, H)>,
+}
+
+impl<H> Endpoint<H> {
+    // Returns the handler for the method along with whether the body of the response should be
+    // stripped, which is only the case when a HEAD request falls back to the GET handler
+    fn handler(&self, method : &Method) -> Option<(&H, &[String], bool)> {
+        let find = |method : &Method| self.handlers.iter().find(|(m, _, _)| m == method);
+        match find(method) {
+            Some((_, names, handler)) => Some((handler, names, false)),
+            None if *method == Method::HEAD => find(&Method::GET).map(|(_, names, handler)| (handler, &names[..], true)),
+            None => None,
+        }
+    }
+    // Adds the methods that this endpoint handles to the allowed methods, HEAD is allowed
+    // whenever GET is
+    fn allow<'a>(&'a self, allowed : &mut AllowedMethods<'a>) {
+        for (method, _, _) in &self.handlers {
+            allowed.insert(method);
+            if *method == Method::GET {
+                allowed.insert(&HEAD);
+            }
+        }
+    }
+}
+
+impl<H> Node<H> {
+    fn new() -> Self {
+        Node {
+            literals : HashMap::new(),
+            wildcards : Vec::new(),
+            catch_alls : Vec::new(),
+            endpoint : None,
+        }
+    }
+    // insert walks down the tree creating any nodes that are missing for the pattern, and returns
+    // the endpoint at the end of the pattern
+    fn insert(&mut self, pattern : &str) -> &mut Endpoint<H> {
+        let mut node = self;
+        let mut segments = pattern.split('/').map(parse_segment).peekable();
+        while let Some(segment) = segments.next() {
+            node = match segment {
+                Segment::Literal(literal) => node.literals.entry(literal.to_string()).or_insert_with(Node::new),
+                Segment::Wildcard { kind, .. } => {
+                    let param = Param::new(kind);
+                    let index = match node.wildcards.iter().position(|(p, _)| *p == param) {
+                        Some(index) => index,
+                        None => {
+                            node.wildcards.push((param, Node::new()));
+                            node.wildcards.len() - 1
+                        }
+                    };
+                    &mut node.wildcards[index].1
+                }
+                Segment::CatchAll { kind, .. } => {
+                    if segments.peek().is_some() {
+                        panic!("the catch all in {} must be the last segment", pattern);
+                    }
+                    let param = Param::new(kind);
+                    let index = match node.catch_alls.iter().position(|(p, _)| *p == param) {
+                        Some(index) => index,
+                        None => {
+                            node.catch_alls.push((param, Endpoint { handlers : Vec::new() }));
+                            node.catch_alls.len() - 1
+                        }
+                    };
+                    return &mut node.catch_alls[index].1;
+                }
+            }
+        }
+        node.endpoint.get_or_insert_with(|| Endpoint { handlers : Vec::new() })
+    }
+    // walk visits every endpoint whose pattern matches the path, from the most specific to the 
+    // least specific, until the visitor returns true. The return value is true if the visitor
+    // stopped the walk.
+    fn walk<'a, F>(&'a self, segments : Segments<'a>, captures : Captures<'a>, constraints : &[Constraint], visit : &mut F) -> bool 
+        where F : FnMut(&'a Endpoint<H>, Captures<'a>) -> bool {
+        // the remainder has to be taken before the path is advanced, in case there is a catch all
+        let remainder = segments.remainder().unwrap_or("");
+        let mut rest = segments;
+        match rest.next() {
+            // the path has ended so this node's endpoint is the match
+            None => {
+                if let Some(endpoint) = &self.endpoint {
+                    if visit(endpoint, captures) {
+                        return true;
+                    }
+                }
+            }
+            // literals are checked before wildcards
+            Some(segment) => {
+                if let Some(child) = self.literals.get(segment) {
+                    if child.walk(rest, captures, constraints, visit) {
+                        return true;
+                    }
+                }
+                for (param, child) in &self.wildcards {
+                    if param.accepts(segment, constraints) {
+                        let mut captures = captures;
+                        captures.push("", segment);
+                        if child.walk(rest, captures, constraints, visit) {
+                            return true;
+                        }
+                    }
+                }
+            }
+        }
+        // a catch all matches the rest of the path, even if there is nothing left
+        for (param, endpoint) in &self.catch_alls {
+            if param.accepts(remainder, constraints) {
+                let mut captures = captures;
+                captures.push("", remainder);
+                if visit(endpoint, captures) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+}
+
+// Returns the names of the wildcards and catch all in the pattern, in the order they appear
+fn capture_names(pattern : &str) -> Vec<String> {
+    pattern.split('/').map(parse_segment).filter_map(|segment| match segment {
+        Segment::Wildcard { name, .. } | Segment::CatchAll { name, .. } => Some(name.to_string()),
+        Segment::Literal(_) => None,
+    }).collect()
+}
+
+/* ============================================================================================ */
+/*     Test Cases                                                                               */
+/* ============================================================================================ */
+#[cfg(test)]
+fn test_router() -> Router<&'static str> {
+    Router::new()
+        .route("GET", "/", "root")
+        .route("GET", "/users", "list_users")
+        .route("POST", "/users", "new_user")
+        .route("GET", "/users/new", "new_user_form")
+        .route("GET", "/users/{id:u64}", "get_user")
+        .route("GET", "/users/{name}", "get_user_by_name")
+        .route("DELETE", "/users/{id:u64}", "delete_user")
+        .route("GET", "/users/{id:u64}/posts/{post}", "get_post")
+        .route("GET", "/static/{*rest}", "static_files")
+}
+
+#[test]
+fn test_dispatch() {
+    use http::request::Builder;
+    let router = test_router();
+    let cases = [
+        ("GET", "/", Some("root")),
+        ("GET", "/users", Some("list_users")),
+        ("POST", "/users", Some("new_user")),
+        ("GET", "/users/new", Some("new_user_form")),
+        ("GET", "/users/42", Some("get_user")),
+        ("GET", "/users/rust", Some("get_user_by_name")),
+        ("DELETE", "/users/42", Some("delete_user")),
+        ("GET", "/users/42/posts/7", Some("get_post")),
+        ("GET", "/static/css/site.css", Some("static_files")),
+        ("GET", "/static", Some("static_files")),
+        ("PUT", "/users", None),
+        ("GET", "/missing", None),
+        ("GET", "/users/rust/posts/7", None),
+    ];
+    for (method, path, expected) in cases.iter() {
+        let request = Builder::new().method(*method).uri(*path).body(()).unwrap();
+        let found = router.dispatch(&request).map(|found| *found.handler());
+        assert_eq!(found, *expected, "{} {}", method, path);
+    }
+}
+
+#[test]
+fn test_dispatch_captures() {
+    use http::request::Builder;
+    let router = test_router();
+    let request = Builder::new().uri("/users/42/posts/7").body(()).unwrap();
+    let captures = router.dispatch(&request).unwrap().captures();
+    assert_eq!(captures.get("id"), Some("42"));
+    assert_eq!(captures.get("post"), Some("7"));
+    let request = Builder::new().uri("/static/css/site.css").body(()).unwrap();
+    let captures = router.dispatch(&request).unwrap().captures();
+    assert_eq!(captures.get("rest"), Some("css/site.css"));
+}
+
+#[test]
+fn test_dispatch_backtracks() {
+    use http::request::Builder;
+    let router = Router::new()
+        .route("GET", "/users/new", "new_user_form")
+        .route("POST", "/users/{name}", "update_user")
+        .constraint("even", |s| s.parse::<u64>().map(|n| n % 2 == 0).unwrap_or(false))
+        .route("GET", "/numbers/{:even}", "even")
+        .route("GET", "/numbers/{}", "odd");
+    let request = Builder::new().method("POST").uri("/users/new").body(()).unwrap();
+    assert_eq!(router.dispatch(&request).map(|found| *found.handler()), Some("update_user"));
+    let request = Builder::new().uri("/numbers/4").body(()).unwrap();
+    assert_eq!(router.dispatch(&request).map(|found| *found.handler()), Some("even"));
+    let request = Builder::new().uri("/numbers/5").body(()).unwrap();
+    assert_eq!(router.dispatch(&request).map(|found| *found.handler()), Some("odd"));
+}
+
+#[test]
+fn test_resolve() {
+    use http::request::Builder;
+    let router = test_router();
+    let request = Builder::new().uri("/users/42").body(()).unwrap();
+    match router.resolve(&request) {
+        Resolution::Found(found) => assert_eq!(*found.handler(), "get_user"),
+        _ => panic!("GET /users/42 should be found"),
+    }
+    let request = Builder::new().method("PUT").uri("/users/42").body(()).unwrap();
+    let resolution = router.resolve(&request);
+    match &resolution {
+        Resolution::MethodNotAllowed(allowed) => {
+            let methods = allowed.iter().map(|m| m.as_str()).collect::<Vec<_>>();
+            assert_eq!(methods, vec!["GET", "HEAD", "DELETE", "OPTIONS"]);
+        }
+        _ => panic!("PUT /users/42 should not be allowed"),
+    }
+    let response = resolution.default_response().unwrap().body(()).unwrap();
+    assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+    assert_eq!(response.headers()[ALLOW], "GET, HEAD, DELETE, OPTIONS");
+    let request = Builder::new().uri("/missing").body(()).unwrap();
+    let resolution = router.resolve(&request);
+    assert!(matches!(resolution, Resolution::NotFound));
+    let response = resolution.default_response().unwrap().body(()).unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert!(response.headers().get(ALLOW).is_none());
+}
+
+#[test]
+fn test_head_and_options() {
+    use http::request::Builder;
+    let router = test_router().route("HEAD", "/", "head_root");
+    let request = Builder::new().method("HEAD").uri("/users/42").body(()).unwrap();
+    let found = router.dispatch(&request).unwrap();
+    assert_eq!(*found.handler(), "get_user");
+    assert!(found.strip_body());
+    let request = Builder::new().method("HEAD").uri("/").body(()).unwrap();
+    let found = router.dispatch(&request).unwrap();
+    assert_eq!(*found.handler(), "head_root");
+    assert!(!found.strip_body());
+    let request = Builder::new().method("OPTIONS").uri("/users").body(()).unwrap();
+    assert!(router.dispatch(&request).is_none());
+    let resolution = router.resolve(&request);
+    match &resolution {
+        Resolution::Options(allowed) => assert!(allowed.contains(&Method::POST)),
+        _ => panic!("OPTIONS /users should be answered by the router"),
+    }
+    let response = resolution.default_response().unwrap().body(()).unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert_eq!(response.headers()[ALLOW], "GET, HEAD, POST, OPTIONS");
+    let request = Builder::new().method("OPTIONS").uri("/missing").body(()).unwrap();
+    assert!(matches!(router.resolve(&request), Resolution::NotFound));
+}
+
+#[test]
+fn test_handle() {
+    use http::request::Builder;
+    type Handler = fn(&Request<()>, Captures) -> Response<&'static str>;
+    let router = Router::<Handler>::new()
+        .route("GET", "/", |_, _| Response::builder().header("content-length", "4").body("body").unwrap())
+        .route("OPTIONS", "/custom", |_, _| Response::builder().body("custom").unwrap());
+    let request = Builder::new().uri("/").body(()).unwrap();
+    assert_eq!(*router.handle(&request).body(), "body");
+    let request = Builder::new().method("HEAD").uri("/").body(()).unwrap();
+    let response = router.handle(&request);
+    assert_eq!(*response.body(), "");
+    assert_eq!(response.headers()["content-length"], "4");
+    let request = Builder::new().method("OPTIONS").uri("/custom").body(()).unwrap();
+    assert_eq!(*router.handle(&request).body(), "custom");
+    let request = Builder::new().method("POST").uri("/").body(()).unwrap();
+    assert_eq!(router.handle(&request).status(), StatusCode::METHOD_NOT_ALLOWED);
+}
+
+#[test]
+#[should_panic]
+fn test_duplicate_route() {
+    let _ = Router::new()
+        .route("GET", "/users", "list_users")
+        .route("GET", "/users", "list_users_again");
+}
+
+#[test]
+#[should_panic]
+fn test_duplicate_route_with_other_names() {
+    let _ = Router::new()
+        .route("GET", "/users/{id}", "get_user")
+        .route("GET", "/users/{name}", "get_user_by_name");
+}
+
+#[test]
+fn test_names_per_method() {
+    use http::request::Builder;
+    let router = Router::new()
+        .route("GET", "/users/{id}", "get_user")
+        .route("DELETE", "/users/{name}", "delete_user");
+    let request = Builder::new().method("DELETE").uri("/users/rust").body(()).unwrap();
+    let found = router.dispatch(&request).unwrap();
+    assert_eq!(found.handler(), &"delete_user");
+    assert_eq!(found.captures().get("name"), Some("rust"));
+    assert_eq!(found.captures().get("id"), None);
+}
+
+#[test]
+#[should_panic]
+fn test_too_many_wildcards() {
+    let _ = Router::new().route("GET", "/{}/{}/{}/{}/{}/{}/{}/{}/{*rest}", "too_many");
+}
+
+#[test]
+#[should_panic]
+fn test_unknown_constraint() {
+    let _ = Router::new().route("GET", "/users/{id:u46}", "get_user");
+}
+
+#[test]
+#[should_panic]
+fn test_constraint_added_after_route() {
+    let _ = Router::new()
+        .route("GET", "/numbers/{:even}", "even")
+        .constraint("even", |s| s.parse::<u64>().map(|n| n % 2 == 0).unwrap_or(false));
+}
@@ -0,0 +1,232 @@
+// MIT License
+// 
+// Copyright (c) 2019 Jonathon Davis
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software. 
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+/* ============================================================================================ */
+/*     Document Structure                                                                       */
+/*          Routes                                                                              */
+/*          Route Table                                                                         */
+/*          Test Cases                                                                          */
+/* ============================================================================================ */
+use std::fmt;
+use http::request::Request;
+use crate::request::RequestMatcher;
+
+/* ============================================================================================ */
+/*     Routes                                                                                   */
+/* ============================================================================================ */
+
+/// Routes is an ordered list of named routes, where each route is a RequestMatcher along with its
+/// handler. A request is given to the first route whose matcher it matches, in the same way as a
+/// chain of handle_fn! calls, but since the routes are values they can be listed for audits and 
+/// startup logs. The Display impl writes the routes as an aligned table.
+/// 
+/// Like the Router, Routes doesn't place any requirements on the handler type.
+/// # Example
+/// ```
+/// use http::request::Builder;
+/// use http_tools::request::{RequestMatcher, Routes};
+/// 
+/// let routes = Routes::new()
+///     .route("health", RequestMatcher::new().with_path("/health").with_method("GET"), 1)
+///     .route("upload", RequestMatcher::new().with_path("/upload").with_method("POST")
+///         .with_header("content-type", "{}"), 2);
+/// 
+/// let request = Builder::new()
+///                 .uri("https://www.rust-lang.org/health")
+///                 .body(()).unwrap();
+/// let route = routes.find(&request).unwrap();
+/// assert_eq!((route.name(), *route.handler()), ("health", 1));
+/// 
+/// // list every route
+/// for route in routes.iter() {
+///     println!("{} {:?} {:?}", route.name(), route.matcher().methods(), route.matcher().path());
+/// }
+/// 
+/// // or print them as a table
+/// assert_eq!(routes.to_string(), "\
+/// METHOD  PATH     REQUIRES                HANDLER
+/// GET     /health  -                       health
+/// POST    /upload  header content-type={}  upload");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Routes<H> {
+    routes : Vec<Route<H>>,
+}
+
+/// A single route of Routes, made up of the name of its handler, its RequestMatcher and its handler
+#[derive(Debug, Clone)]
+pub struct Route<H> {
+    name : String,
+    matcher : RequestMatcher,
+    handler : H,
+}
+
+impl<H> Route<H> {
+    /// Returns the name the route was added with
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// Returns the matcher that a request must match to be given to the route
+    pub fn matcher(&self) -> &RequestMatcher {
+        &self.matcher
+    }
+    /// Returns the handler of the route
+    pub fn handler(&self) -> &H {
+        &self.handler
+    }
+}
+
+impl<H> Routes<H> {
+    /// Creates an empty list of routes
+    pub fn new() -> Self {
+        Routes { routes : Vec::new() }
+    }
+    /// Adds a route after the routes that were already added, so it is only given the requests 
+    /// that don't match an earlier route
+    pub fn route(mut self, name : &str, matcher : RequestMatcher, handler : H) -> Self {
+        self.routes.push(Route { name : name.to_string(), matcher, handler });
+        self
+    }
+    /// Finds the first route whose matcher matches the request, returning None if there isn't one
+    pub fn find<R>(&self, request : &Request<R>) -> Option<&Route<H>> {
+        self.routes.iter().find(|route| route.matcher.matches(request))
+    }
+    /// Returns an iterator over the routes in the order they were added
+    pub fn iter(&self) -> impl '_ + Iterator<Item=&Route<H>> {
+        self.routes.iter()
+    }
+    /// Returns the number of routes
+    pub fn len(&self) -> usize {
+        self.routes.len()
+    }
+    /// Returns true if there are no routes
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+}
+
+impl<H> Default for Routes<H> {
+    fn default() -> Self {
+        Routes::new()
+    }
+}
+
+/* ============================================================================================ */
+/*     Route Table                                                                              */
+/* ============================================================================================ */
+
+impl<H> fmt::Display for Routes<H> {
+    // writes a header row followed by a row for each route, every column except the last is
+    // padded to the width of its widest cell so that the columns line up
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        let mut rows = vec![[String::from("METHOD"), String::from("PATH"), String::from("REQUIRES"), String::from("HANDLER")]];
+        rows.extend(self.routes.iter().map(|route| [
+            methods_cell(&route.matcher), 
+            path_cell(&route.matcher), 
+            requires_cell(&route.matcher), 
+            route.name.clone(),
+        ]));
+        let mut widths = [0; 3];
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row.iter()) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+        for (index, row) in rows.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            for (width, cell) in widths.iter().zip(row.iter()) {
+                write!(f, "{:width$}  ", cell, width = width)?;
+            }
+            write!(f, "{}", row[3])?;
+        }
+        Ok(())
+    }
+}
+
+// The methods of the route separated by commas, or * if the route allows any method
+pub(crate) fn methods_cell(matcher : &RequestMatcher) -> String {
+    match matcher.methods() {
+        [] => String::from("*"),
+        methods => methods.iter().map(|method| method.as_str()).collect::<Vec<_>>().join(","),
+    }
+}
+
+// The path pattern of the route, a prefix is marked with a trailing ..., and a route without a
+// path matches any path
+pub(crate) fn path_cell(matcher : &RequestMatcher) -> String {
+    match (matcher.path(), matcher.is_prefix()) {
+        (Some(pattern), true) => format!("{}...", pattern),
+        (Some(pattern), false) => pattern.to_string(),
+        (None, _) => String::from("*"),
+    }
+}
+
+// The scheme, headers and queries the route requires separated by spaces, or - if there are none
+fn requires_cell(matcher : &RequestMatcher) -> String {
+    let requires = matcher.scheme().map(|scheme| format!("scheme {}", scheme)).into_iter()
+        .chain(matcher.headers().map(|(key, value)| format!("header {}={}", key, value)))
+        .chain(matcher.queries().map(|(key, value)| format!("query {}={}", key, value)))
+        .collect::<Vec<_>>();
+    match requires.is_empty() {
+        true => String::from("-"),
+        false => requires.join(" "),
+    }
+}
+
+/* ============================================================================================ */
+/*     Test Cases                                                                               */
+/* ============================================================================================ */
+#[test]
+fn test_routes_find() {
+    use http::request::Builder;
+    let routes = Routes::new()
+        .route("item", RequestMatcher::new().with_path("/item/{}").with_method("GET"), "item")
+        .route("any_item", RequestMatcher::new().with_path_prefix("/item"), "any_item")
+        .route("fallback", RequestMatcher::new(), "fallback");
+    let find = |uri : &str, method : &str| {
+        let request = Builder::new().uri(uri).method(method).body(()).unwrap();
+        routes.find(&request).map(|route| *route.handler())
+    };
+    assert_eq!(find("/item/1", "GET"), Some("item"));
+    assert_eq!(find("/item/1", "POST"), Some("any_item"));
+    assert_eq!(find("/item/1/parts", "GET"), Some("any_item"));
+    assert_eq!(find("/other", "GET"), Some("fallback"));
+    assert_eq!(routes.len(), 3);
+    assert_eq!(Routes::<()>::new().find(&Builder::new().body(()).unwrap()).map(|route| route.name()), None);
+}
+
+#[test]
+fn test_routes_display() {
+    let routes = Routes::new()
+        .route("get_item", RequestMatcher::new().with_path("/item/{}").with_method("GET").with_method("HEAD"), ())
+        .route("static_files", RequestMatcher::new().with_path_prefix("/static").with_scheme("https"), ())
+        .route("search", RequestMatcher::new().with_path("/search").with_query("q", "{}").with_header("accept", "{}"), ());
+    let expected = [
+        "METHOD    PATH        REQUIRES                     HANDLER",
+        "GET,HEAD  /item/{}    -                            get_item",
+        "*         /static...  scheme https                 static_files",
+        "*         /search     header accept={} query q={}  search",
+    ];
+    assert_eq!(routes.to_string(), expected.join("\n"));
+    assert_eq!(Routes::<()>::new().to_string(), "METHOD  PATH  REQUIRES  HANDLER");
+}
@@ -20,6 +20,8 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 use http::response::Response;
+use http::header::{HeaderValue, SET_COOKIE};
+use crate::cookie::SetCookie;
 
 /// The Extension trait provides additional methods to the Http Response type
 pub trait Extension {
@@ -36,3 +38,34 @@ impl<R> Extension for Response<R> {
         Some(self)
     }
 }
+
+/// Returns an iterator over the cookies set by a response, each Set-Cookie header is parsed into
+/// a SetCookie and headers that can't be parsed are skipped. 
+/// 
+/// # Example
+/// ```
+/// use http::response::Builder;
+/// use http_tools::response::set_cookie_iter;
+///
+/// // given an  http response
+/// let response = Builder::new()
+///                 .header("set-cookie", "session=abc123; HttpOnly")
+///                 .header("set-cookie", "theme=dark; Max-Age=3600")
+///                 .body(()).unwrap();
+/// 
+/// // use the http_tools function to create an iterator
+/// for cookie in set_cookie_iter(&response){
+///     println!("{} {}", cookie.name(), cookie.value())
+/// }
+/// 
+/// // will print out 
+/// // session abc123
+/// // theme dark
+/// ```
+pub fn set_cookie_iter<R>(response : &Response<R>) -> impl '_ + Iterator<Item=SetCookie<'_>> {
+    response.headers()
+        .get_all(SET_COOKIE)
+        .into_iter()
+        .filter_map(|value : &HeaderValue| value.to_str().ok())
+        .filter_map(SetCookie::parse)
+}
@@ -26,9 +26,13 @@
 /*          impl Filter for Option<Response>                                                    */
 /*          Test Cases                                                                          */
 /* ============================================================================================ */
+use std::ops::RangeBounds;
 use http::response::Response;
 use http::header::HeaderValue;
 use http::status::StatusCode;
+use crate::header::{content_type, list_contains};
+use crate::predicate::Predicate;
+use crate::response::set_cookie_iter;
 
 const WILDCARD : &str = "{}";
 
@@ -72,7 +76,8 @@ pub trait Filter<'a, R> {
     /// Checks to see if the response has the specified key and value. The wildcard '{}'
     /// pattern can be used in either the key or the value string. The function returns
     /// Some(response) if the header with the key and value are found or None if they are
-    /// absent. 
+    /// absent. When a header is repeated every value is checked, the value is compared with
+    /// the whole header value, see filter_header_contains for matching list elements.
     /// # Example
     /// ```
     /// use http::response::Builder;
@@ -92,6 +97,46 @@ pub trait Filter<'a, R> {
     /// assert!(filter.is_some());
     /// ```
     fn filter_header<T>(self, key : &str, value : T) -> Self where T : PartialEq<HeaderValue> + PartialEq<&'static str>;
+    /// Checks to see if the response has a list header where one of the comma separated elements
+    /// is the value, such as Upgrade in 'Connection: keep-alive, Upgrade'. Every value of a
+    /// repeated header is checked, and the elements are trimmed before they are compared. The
+    /// wildcard '{}' pattern can be used in either the key or the value string.
+    /// # Example
+    /// ```
+    /// use http::response::Builder;
+    /// use http_tools::response::{Extension, Filter};
+    /// 
+    /// // Response Builder found in http crate
+    /// let response = Builder::new()
+    ///                 .header("connection", "keep-alive, Upgrade")
+    ///                 .body(()).unwrap();
+    /// 
+    /// // matches as Upgrade is an element of the list
+    /// let filter = response.filter().filter_header_contains("connection", "Upgrade");
+    /// assert!(filter.is_some());
+    /// 
+    /// // doesn't match as the comparison is case sensitive
+    /// let filter = response.filter().filter_header_contains("connection", "upgrade");
+    /// assert!(filter.is_none());
+    /// ```
+    fn filter_header_contains(self, key : &str, value : &str) -> Self;
+    /// Works the same as filter_header_contains except that the elements are compared without
+    /// regard to case, which is how tokens such as those in the Connection, Upgrade and
+    /// Transfer-Encoding headers are compared.
+    /// # Example
+    /// ```
+    /// use http::response::Builder;
+    /// use http_tools::response::{Extension, Filter};
+    /// 
+    /// // Response Builder found in http crate
+    /// let response = Builder::new()
+    ///                 .header("connection", "keep-alive, Upgrade")
+    ///                 .body(()).unwrap();
+    /// 
+    /// let filter = response.filter().filter_header_contains_ignore_case("connection", "upgrade");
+    /// assert!(filter.is_some());
+    /// ```
+    fn filter_header_contains_ignore_case(self, key : &str, value : &str) -> Self;
     /// filter_custom allows for a custom function filter. The filter will be given a &Response and
     /// will output a bool. if the bool is true, then function returns Some, if it is false then the
     /// function will return None
@@ -109,10 +154,52 @@ pub trait Filter<'a, R> {
     /// assert!(filter.is_some());
     /// ```
     fn filter_custom(self, func : fn(&Response<R>) -> bool) -> Self;
+    /// filter_fn works the same as filter_custom, except that it accepts any closure rather than
+    /// only a fn, so the closure can capture state such as configuration. To reuse the same closure
+    /// across many filters use filter_predicate.
+    /// # Example
+    /// ```
+    /// use http::response::Builder;
+    /// use http_tools::response::{Extension, Filter};
+    /// // Response Builder found in http crate
+    /// let response = Builder::new()
+    ///                     .status(503)
+    ///                     .body(()).unwrap();
+    /// 
+    /// // the status codes that should be retried, loaded at startup
+    /// let retry = vec![502, 503, 504];
+    /// 
+    /// // this will match as 503 should be retried
+    /// let filter = response.filter().filter_fn(|response| retry.contains(&response.status().as_u16()));
+    /// assert!(filter.is_some());
+    /// ```
+    fn filter_fn<F>(self, func : F) -> Self where F : FnOnce(&Response<R>) -> bool;
+    /// filter_predicate tests the response with a Predicate, which is a shared function that can
+    /// capture state. The Predicate can be built once and cloned into as many routes as needed.
+    /// # Example
+    /// ```
+    /// use http::response::{Builder, Response};
+    /// use http_tools::response::{Extension, Filter};
+    /// use http_tools::predicate::Predicate;
+    /// // Response Builder found in http crate
+    /// let response = Builder::new()
+    ///                     .status(503)
+    ///                     .body(()).unwrap();
+    /// 
+    /// // the status codes that should be retried, loaded at startup and shared
+    /// let retry = vec![502, 503, 504];
+    /// let retryable = Predicate::new(move |response : &Response<()>| retry.contains(&response.status().as_u16()));
+    /// 
+    /// // this will match as 503 should be retried
+    /// let filter = response.filter().filter_predicate(&retryable);
+    /// assert!(filter.is_some());
+    /// ```
+    fn filter_predicate(self, predicate : &Predicate<Response<R>>) -> Self;
     /// filter_status checks to see if the status of the Response is equal to the response status.
     /// The filter will return Some(&Response) if the status codes are equal and None otherwise. If 
     /// filtering over a wider variety of errors use the filter_status_success, filter_status_client_error,
-    /// filter_status_server_error, filter_status_redirection and filter_status_informational
+    /// filter_status_server_error, filter_status_redirection and filter_status_informational filters,
+    /// or filter_status_range for any range of status codes
     /// # Example
     /// ```
     /// use http::response::Builder;
@@ -128,6 +215,230 @@ pub trait Filter<'a, R> {
     /// assert!(filter.is_some());
     /// ```
     fn filter_status<T>(self, status : T) -> Self where StatusCode : PartialEq<T>;
+    /// filter_status_informational checks to see if the status of the Response is informational,
+    /// meaning the status is in the range 100-199.
+    /// # Example
+    /// ```
+    /// use http::response::Builder;
+    /// use http_tools::response::{Extension, Filter};
+    /// // Response Builder found in http crate
+    /// let response = Builder::new()
+    ///                     .status(101)
+    ///                     .body(()).unwrap();
+    /// 
+    /// // this will match
+    /// let filter = response.filter().filter_status_informational();
+    /// assert!(filter.is_some());
+    /// // this will not
+    /// let filter = response.filter().filter_status_success();
+    /// assert!(filter.is_none());
+    /// ```
+    fn filter_status_informational(self) -> Self;
+    /// filter_status_success checks to see if the status of the Response is a success, meaning
+    /// the status is in the range 200-299.
+    /// # Example
+    /// ```
+    /// use http::response::Builder;
+    /// use http_tools::response::{Extension, Filter};
+    /// // Response Builder found in http crate
+    /// let response = Builder::new()
+    ///                     .status(204)
+    ///                     .body(()).unwrap();
+    /// 
+    /// // this will match
+    /// let filter = response.filter().filter_status_success();
+    /// assert!(filter.is_some());
+    /// // this will not
+    /// let filter = response.filter().filter_status_client_error();
+    /// assert!(filter.is_none());
+    /// ```
+    fn filter_status_success(self) -> Self;
+    /// filter_status_redirection checks to see if the status of the Response is a redirection,
+    /// meaning the status is in the range 300-399.
+    /// # Example
+    /// ```
+    /// use http::response::Builder;
+    /// use http_tools::response::{Extension, Filter};
+    /// // Response Builder found in http crate
+    /// let response = Builder::new()
+    ///                     .status(302)
+    ///                     .body(()).unwrap();
+    /// 
+    /// // this will match
+    /// let filter = response.filter().filter_status_redirection();
+    /// assert!(filter.is_some());
+    /// // this will not
+    /// let filter = response.filter().filter_status_success();
+    /// assert!(filter.is_none());
+    /// ```
+    fn filter_status_redirection(self) -> Self;
+    /// filter_status_client_error checks to see if the status of the Response is a client error,
+    /// meaning the status is in the range 400-499.
+    /// # Example
+    /// ```
+    /// use http::response::Builder;
+    /// use http_tools::response::{Extension, Filter};
+    /// // Response Builder found in http crate
+    /// let response = Builder::new()
+    ///                     .status(404)
+    ///                     .body(()).unwrap();
+    /// 
+    /// // this will match
+    /// let filter = response.filter().filter_status_client_error();
+    /// assert!(filter.is_some());
+    /// // this will not
+    /// let filter = response.filter().filter_status_server_error();
+    /// assert!(filter.is_none());
+    /// ```
+    fn filter_status_client_error(self) -> Self;
+    /// filter_status_server_error checks to see if the status of the Response is a server error,
+    /// meaning the status is in the range 500-599.
+    /// # Example
+    /// ```
+    /// use http::response::Builder;
+    /// use http_tools::response::{Extension, Filter};
+    /// // Response Builder found in http crate
+    /// let response = Builder::new()
+    ///                     .status(503)
+    ///                     .body(()).unwrap();
+    /// 
+    /// // this will match
+    /// let filter = response.filter().filter_status_server_error();
+    /// assert!(filter.is_some());
+    /// // this will not
+    /// let filter = response.filter().filter_status_client_error();
+    /// assert!(filter.is_none());
+    /// ```
+    fn filter_status_server_error(self) -> Self;
+    /// filter_status_range checks to see if the status code of the Response is within the range,
+    /// any of the range types can be used such as 400..500, 500.. or 200..=204.
+    /// # Example
+    /// ```
+    /// use http::response::Builder;
+    /// use http_tools::response::{Extension, Filter};
+    /// // Response Builder found in http crate
+    /// let response = Builder::new()
+    ///                     .status(429)
+    ///                     .body(()).unwrap();
+    /// 
+    /// // this will match
+    /// let filter = response.filter().filter_status_range(400..500);
+    /// assert!(filter.is_some());
+    /// // this will not
+    /// let filter = response.filter().filter_status_range(500..);
+    /// assert!(filter.is_none());
+    /// ```
+    fn filter_status_range<B>(self, range : B) -> Self where B : RangeBounds<u16>;
+    /// Checks to see if the Content-Type of the response is the given media type, the parameters
+    /// of the Content-Type such as the charset are ignored and the media type is compared without
+    /// regard to case. The media type can use a '*' wildcard, such as text/*.
+    /// # Example
+    /// ```
+    /// use http::response::Builder;
+    /// use http_tools::response::{Extension, Filter};
+    /// use http_tools::header::mime;
+    /// // Response Builder found in http crate
+    /// let response = Builder::new()
+    ///                     .header("content-type", "text/html; charset=utf-8")
+    ///                     .body(()).unwrap();
+    /// 
+    /// // this will match as the charset is ignored
+    /// let filter = response.filter().filter_content_type(mime::HTML);
+    /// assert!(filter.is_some());
+    /// ```
+    fn filter_content_type(self, media_type : &str) -> Self;
+    /// Checks to see if the response sets a cookie with the given name, the wildcard '{}' can be
+    /// used to check that the response sets any cookie. The attributes of the cookie can be 
+    /// checked with response::set_cookie_iter.
+    /// # Example
+    /// ```
+    /// use http::response::Builder;
+    /// use http_tools::response::{Extension, Filter};
+    /// // Response Builder found in http crate
+    /// let response = Builder::new()
+    ///                     .header("set-cookie", "session=abc123; HttpOnly")
+    ///                     .body(()).unwrap();
+    /// 
+    /// // this will match as the response sets the session cookie
+    /// let filter = response.filter().filter_set_cookie("session");
+    /// assert!(filter.is_some());
+    /// // this will not
+    /// let filter = response.filter().filter_set_cookie("theme");
+    /// assert!(filter.is_none());
+    /// ```
+    fn filter_set_cookie(self, name : &str) -> Self;
+    /// filter_any passes the response if either of the filters pass, which allows for alternatives
+    /// within a chain. Each filter is given the current Option<&Response> and can chain any number 
+    /// of filters, the second filter is only run if the first one fails.
+    /// # Example
+    /// ```
+    /// use http::response::Builder;
+    /// use http_tools::response::{Extension, Filter};
+    /// // Response Builder found in http crate
+    /// let response = Builder::new()
+    ///                     .status(404)
+    ///                     .body(()).unwrap();
+    /// 
+    /// // this will match as the second filter passes
+    /// let filter = response.filter().filter_any(|r| r.filter_status(200), |r| r.filter_status(404));
+    /// assert!(filter.is_some());
+    /// ```
+    fn filter_any<A, B>(self, first : A, second : B) -> Self where Self : Sized, A : FnOnce(Self) -> Self, B : FnOnce(Self) -> Self;
+    /// filter_any_of passes the response if any of the filters pass, the filters are run in order
+    /// until one of them passes. Closures that don't capture anything can be given in the slice.
+    /// # Example
+    /// ```
+    /// use http::response::Builder;
+    /// use http_tools::response::{Extension, Filter};
+    /// // Response Builder found in http crate
+    /// let response = Builder::new()
+    ///                     .status(404)
+    ///                     .body(()).unwrap();
+    /// 
+    /// // this will match as the second filter passes
+    /// let filter = response.filter().filter_any_of(&[|r| r.filter_status(200), |r| r.filter_status(404), |r| r.filter_status_server_error()]);
+    /// assert!(filter.is_some());
+    /// ```
+    fn filter_any_of(self, filters : &[fn(Self) -> Self]) -> Self where Self : Sized;
+    /// filter_not passes the response if the filter fails, and fails the response if the filter passes.
+    /// A response that has already failed a filter earlier in the chain stays failed.
+    /// # Example
+    /// ```
+    /// use http::response::Builder;
+    /// use http_tools::response::{Extension, Filter};
+    /// // Response Builder found in http crate
+    /// let response = Builder::new()
+    ///                     .status(404)
+    ///                     .body(()).unwrap();
+    /// 
+    /// // this will match as the filter fails
+    /// let filter = response.filter().filter_not(|r| r.filter_status(200));
+    /// assert!(filter.is_some());
+    /// // this will not
+    /// let filter = response.filter().filter_not(|r| r.filter_status(404));
+    /// assert!(filter.is_none());
+    /// ```
+    fn filter_not<F>(self, filter : F) -> Self where Self : Sized, F : FnOnce(Self) -> Self;
+    /// filter_group runs the filter as a single step of the chain, which allows a group of filters 
+    /// to be written once as a function and reused across chains, or to be combined with filter_not.
+    /// # Example
+    /// ```
+    /// use http::response::Builder;
+    /// use http_tools::response::{Extension, Filter};
+    /// // Response Builder found in http crate
+    /// let response = Builder::new()
+    ///                     .status(404)
+    ///                     .body(()).unwrap();
+    /// 
+    /// // a group of filters written as a function
+    /// fn is_not_found<'a, R>(filter : Option<&'a http::Response<R>>) -> Option<&'a http::Response<R>> {
+    ///     filter.filter_not(|r| r.filter_status(200)).filter_any(|r| r.filter_status(404), |r| r.filter_status_server_error())
+    /// }
+    /// 
+    /// let filter = response.filter().filter_group(is_not_found);
+    /// assert!(filter.is_some());
+    /// ```
+    fn filter_group<F>(self, filter : F) -> Self where Self : Sized, F : FnOnce(Self) -> Self;
 }
 
 /* ============================================================================================ */
@@ -154,7 +465,7 @@ impl<'a, R> Filter<'a, R> for Option<&Response<R>>{
                 let map = response.headers();
                 // If the value is {} and there are entries in the header map
                 // the return Some response as any value would match
-                if value == WILDCARD && map.len() > 0 {
+                if value == WILDCARD && !map.is_empty() {
                     return Some(response);
                 }
                 // Iterate through the different values to see if any values
@@ -166,17 +477,40 @@ impl<'a, R> Filter<'a, R> for Option<&Response<R>>{
                     }
                 }
             } else {
-                // Get the key and check if it's value is equal to the inputed value
+                // Get the values of the key and check if any are equal to the inputed value
                 // otherwise fall through to the end and return None
-                match response.headers().get(key) {
-                    Some(v) if value == *v || value == WILDCARD => return Some(response),
-                    _ => (),
+                let mut values = response.headers().get_all(key).iter().peekable();
+                if values.peek().is_some() && value == WILDCARD {
+                    return Some(response);
+                }
+                if values.any(|v| value == *v) {
+                    return Some(response);
                 }
             }
         }
         // If the filter broke out, or self was None then return None
         None
     }
+    // The filter_header_contains function for Option<&Response> splits every value of the header
+    // into its list elements and checks if any of them are equal to the inputed value
+    fn filter_header_contains(self, key : &str, value : &str) -> Self {
+        if let Some(response) = self {
+            if list_contains(response.headers(), key, value, false) {
+                return self;
+            }
+        }
+        None
+    }
+    // The filter_header_contains_ignore_case function works the same as filter_header_contains
+    // except that the elements are compared without regard to case
+    fn filter_header_contains_ignore_case(self, key : &str, value : &str) -> Self {
+        if let Some(response) = self {
+            if list_contains(response.headers(), key, value, true) {
+                return self;
+            }
+        }
+        None
+    }
     // The filter_scheme function for Option<&Response> first checks to see that the value of
     // self is Some, then checks to see if the response scheme is equal to the inputed scheme.
     fn filter_custom(self, func : fn(&Response<R>) -> bool) -> Self {
@@ -188,6 +522,52 @@ impl<'a, R> Filter<'a, R> for Option<&Response<R>>{
         }
        None
     }
+    // The filter_fn function for Option<&Response> works the same as filter_custom
+    fn filter_fn<F>(self, func : F) -> Self where F : FnOnce(&Response<R>) -> bool {
+        if let Some(response) = self {
+            if func(response) {
+                return self;
+            }
+        }
+        None
+    }
+    // The filter_predicate function for Option<&Response> tests the response with the predicate
+    fn filter_predicate(self, predicate : &Predicate<Response<R>>) -> Self {
+        if let Some(response) = self {
+            if predicate.test(response) {
+                return self;
+            }
+        }
+        None
+    }
+    // The filter_any function for Option<&Response> runs the first filter, and only runs the
+    // second filter if the first one failed. The response is passed along as it was before the
+    // filters, as the alternatives only decide if it passes.
+    fn filter_any<A, B>(self, first : A, second : B) -> Self where A : FnOnce(Self) -> Self, B : FnOnce(Self) -> Self {
+        if self.is_some() && (first(self).is_some() || second(self).is_some()) {
+            return self;
+        }
+        None
+    }
+    // The filter_any_of function for Option<&Response> runs each filter until one passes
+    fn filter_any_of(self, filters : &[fn(Self) -> Self]) -> Self {
+        if self.is_some() && filters.iter().any(|filter| filter(self).is_some()) {
+            return self;
+        }
+        None
+    }
+    // The filter_not function for Option<&Response> inverts the result of the filter, but only
+    // if the response hasn't already failed
+    fn filter_not<F>(self, filter : F) -> Self where F : FnOnce(Self) -> Self {
+        if self.is_some() && filter(self).is_none() {
+            return self;
+        }
+        None
+    }
+    // The filter_group function for Option<&Response> simply runs the filter
+    fn filter_group<F>(self, filter : F) -> Self where F : FnOnce(Self) -> Self {
+        filter(self)
+    }
     // The filter_status function for Option<&Response> checks to see if the given status
     // is equal to the response status. The value will be Some if they are equal and None
     // if they are not
@@ -199,6 +579,54 @@ impl<'a, R> Filter<'a, R> for Option<&Response<R>>{
         }
         None
     }
+    // The status class filters for Option<&Response> each check a range of status codes using
+    // filter_status_range
+    fn filter_status_informational(self) -> Self {
+        self.filter_status_range(100..200)
+    }
+    fn filter_status_success(self) -> Self {
+        self.filter_status_range(200..300)
+    }
+    fn filter_status_redirection(self) -> Self {
+        self.filter_status_range(300..400)
+    }
+    fn filter_status_client_error(self) -> Self {
+        self.filter_status_range(400..500)
+    }
+    fn filter_status_server_error(self) -> Self {
+        self.filter_status_range(500..600)
+    }
+    // The filter_status_range function for Option<&Response> checks to see if the status code
+    // of the response is contained by the range
+    fn filter_status_range<B>(self, range : B) -> Self where B : RangeBounds<u16> {
+        if let Some(response) = self {
+            if range.contains(&response.status().as_u16()) {
+                return self;
+            }
+        }
+        None
+    }
+    // The filter_content_type function for Option<&Response> parses the Content-Type header
+    // and compares its type and subtype, a missing or invalid Content-Type fails the filter
+    fn filter_content_type(self, media_type : &str) -> Self {
+        if let Some(response) = self {
+            match content_type(response.headers()) {
+                Some(content_type) if content_type.is(media_type) => return self,
+                _ => (),
+            }
+        }
+        None
+    }
+    // The filter_set_cookie function for Option<&Response> parses each Set-Cookie header using
+    // response::set_cookie_iter and checks the name of the cookie
+    fn filter_set_cookie(self, name : &str) -> Self {
+        if let Some(response) = self {
+            if set_cookie_iter(response).any(|cookie| name == WILDCARD || cookie.name() == name) {
+                return self;
+            }
+        }
+        None
+    }
 }
 
 /* ============================================================================================ */
@@ -251,4 +679,143 @@ fn test_status() {
     assert!(filter.is_none());
     let filter = response.filter().filter_status(1000);
     assert!(filter.is_none());
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_content_type() {
+    use http::response::Builder;
+    use crate::response::Extension;
+    let response = Builder::new().header("content-type", "application/json").body(()).unwrap();
+    assert!(response.filter().filter_content_type("application/json").is_some());
+    assert!(response.filter().filter_content_type("text/html").is_none());
+}
+
+#[test]
+fn test_header_repeated() {
+    use http::response::Builder;
+    use crate::response::Extension;
+    let response = Builder::new()
+        .header("accept-encoding", "gzip")
+        .header("accept-encoding", "br")
+        .body(()).unwrap();
+    let filter = response.filter().filter_header("accept-encoding", "br");
+    assert!(filter.is_some());
+    let filter = response.filter().filter_header("accept-encoding", "{}");
+    assert!(filter.is_some());
+    let filter = response.filter().filter_header("accept-encoding", "deflate");
+    assert!(filter.is_none());
+}
+
+#[test]
+fn test_header_contains() {
+    use http::response::Builder;
+    use crate::response::Extension;
+    let response = Builder::new()
+        .header("connection", "keep-alive, Upgrade")
+        .header("connection", "close")
+        .body(()).unwrap();
+    let filter = response.filter().filter_header_contains("connection", "Upgrade");
+    assert!(filter.is_some());
+    let filter = response.filter().filter_header_contains("connection", "close");
+    assert!(filter.is_some());
+    let filter = response.filter().filter_header_contains("connection", "keep-alive, Upgrade");
+    assert!(filter.is_none());
+    let filter = response.filter().filter_header_contains("connection", "upgrade");
+    assert!(filter.is_none());
+    let filter = response.filter().filter_header_contains_ignore_case("connection", "UPGRADE");
+    assert!(filter.is_some());
+    let filter = response.filter().filter_header_contains_ignore_case("{}", "Close");
+    assert!(filter.is_some());
+    let filter = response.filter().filter_header_contains_ignore_case("upgrade", "{}");
+    assert!(filter.is_none());
+}
+
+#[test]
+fn test_set_cookie() {
+    use http::response::Builder;
+    use crate::response::Extension;
+    let response = Builder::new()
+        .header("set-cookie", "session=abc123; HttpOnly")
+        .header("set-cookie", "theme=dark")
+        .body(()).unwrap();
+    assert!(response.filter().filter_set_cookie("session").is_some());
+    assert!(response.filter().filter_set_cookie("theme").is_some());
+    assert!(response.filter().filter_set_cookie("{}").is_some());
+    assert!(response.filter().filter_set_cookie("HttpOnly").is_none());
+    let response = Builder::new().body(()).unwrap();
+    assert!(response.filter().filter_set_cookie("{}").is_none());
+}
+
+#[test]
+fn test_status_class() {
+    use http::response::Builder;
+    use crate::response::Extension;
+    // returns which of the class filters the status passes
+    let classes = |status : u16| {
+        let response = Builder::new().status(status).body(()).unwrap();
+        let filter = response.filter();
+        [
+            filter.filter_status_informational().is_some(),
+            filter.filter_status_success().is_some(),
+            filter.filter_status_redirection().is_some(),
+            filter.filter_status_client_error().is_some(),
+            filter.filter_status_server_error().is_some(),
+        ]
+    };
+    assert_eq!(classes(100), [true, false, false, false, false]);
+    assert_eq!(classes(199), [true, false, false, false, false]);
+    assert_eq!(classes(200), [false, true, false, false, false]);
+    assert_eq!(classes(299), [false, true, false, false, false]);
+    assert_eq!(classes(301), [false, false, true, false, false]);
+    assert_eq!(classes(404), [false, false, false, true, false]);
+    assert_eq!(classes(499), [false, false, false, true, false]);
+    assert_eq!(classes(500), [false, false, false, false, true]);
+    assert_eq!(classes(599), [false, false, false, false, true]);
+}
+
+#[test]
+fn test_status_range() {
+    use http::response::Builder;
+    use crate::response::Extension;
+    let response = Builder::new().status(404).body(()).unwrap();
+    assert!(response.filter().filter_status_range(400..500).is_some());
+    assert!(response.filter().filter_status_range(404..=404).is_some());
+    assert!(response.filter().filter_status_range(..405).is_some());
+    assert!(response.filter().filter_status_range(..).is_some());
+    assert!(response.filter().filter_status_range(405..).is_none());
+    assert!(response.filter().filter_status_range(400..404).is_none());
+}
+
+#[test]
+fn test_combinators() {
+    use http::response::Builder;
+    use crate::response::Extension;
+    let response = Builder::new().status(404).body(()).unwrap();
+    let filter = response.filter().filter_any(|r| r.filter_status(200), |r| r.filter_status(404));
+    assert!(filter.is_some());
+    let filter = response.filter().filter_any_of(&[|r| r.filter_status(200), |r| r.filter_status_server_error()]);
+    assert!(filter.is_none());
+    let filter = response.filter().filter_not(|r| r.filter_status_success());
+    assert!(filter.is_some());
+    let filter = response.filter().filter_not(|r| r.filter_status_client_error());
+    assert!(filter.is_none());
+    let filter = response.filter().filter_group(|r| r.filter_not(|r| r.filter_status(500)));
+    assert!(filter.is_some());
+    let filter = response.filter().filter_status(200).filter_not(|r| r.filter_status(500));
+    assert!(filter.is_none());
+}
+
+#[test]
+fn test_fn_and_predicate() {
+    use http::response::Builder;
+    use crate::response::Extension;
+    let response = Builder::new().status(503).body(()).unwrap();
+    let retry = [502u16, 503, 504];
+    let filter = response.filter().filter_fn(|response| retry.contains(&response.status().as_u16()));
+    assert!(filter.is_some());
+    let filter = response.filter().filter_fn(|_| false);
+    assert!(filter.is_none());
+    let retryable = Predicate::new(move |response : &Response<()>| retry.contains(&response.status().as_u16()));
+    assert!(response.filter().filter_predicate(&retryable).is_some());
+    assert!(response.filter().filter_predicate(&retryable.not()).is_none());
+}
@@ -25,6 +25,6 @@
 mod filter;
 mod extension;
 pub use self::{
-    extension::Extension,
+    extension::{set_cookie_iter, Extension},
     filter::Filter,
 };
\ No newline at end of file